@@ -0,0 +1,185 @@
+use ecosim::config::*;
+use ecosim::replay::{Playback, Replay};
+use ecosim::stats::CsvRecorder;
+use ecosim::terrain::TerrainSource;
+use ecosim::world::Boundary;
+use ecosim::util::*;
+
+const USAGE: &str = "usage: ecosim-headless [--seed N] [--steps N] [--settings FILE] [--size WxH] [--terrain flat|random|FILE] [--boundary torus|reflect|closed] [--soil] [--threads N] [--replay FILE] [--csv FILE] [--quiet]";
+
+struct Args {
+    seed: Option<u64>,
+    // default 10k, or the whole replay
+    steps: Option<u64>,
+    settings: Option<String>,
+    size: Option<(i32, i32)>,
+    terrain: Option<TerrainSource>,
+    boundary: Option<Boundary>,
+    soil: bool,
+    threads: Option<u32>,
+    replay: Option<String>,
+    csv: Option<String>,
+    quiet: bool,
+}
+
+fn parse_args() -> Result<Args, String> {
+    let mut args = Args {
+        seed: None,
+        steps: None,
+        settings: None,
+        size: None,
+        terrain: None,
+        boundary: None,
+        soil: false,
+        threads: None,
+        replay: None,
+        csv: None,
+        quiet: false,
+    };
+
+    let mut it = std::env::args().skip(1);
+    while let Some(a) = it.next() {
+        match a.as_str() {
+            "--seed" => args.seed = Some(next_num(&mut it, "--seed")?),
+            "--steps" => args.steps = Some(next_num(&mut it, "--steps")?),
+            "--settings" => args.settings = Some(it.next().ok_or("--settings needs a file")?),
+            "--size" => args.size = Some(parse_size(&it.next().ok_or("--size needs WxH")?)?),
+            "--terrain" => args.terrain = Some(TerrainSource::parse(&it.next().ok_or("--terrain needs flat, random or a file")?)?),
+            "--boundary" => {
+                let b = it.next().ok_or("--boundary needs torus, reflect or closed")?;
+                args.boundary = Some(Boundary::parse(&b).ok_or(format!("--boundary: bad value `{}`", b))?);
+            }
+            "--threads" => args.threads = Some(next_num(&mut it, "--threads")? as u32),
+            "--replay" => args.replay = Some(it.next().ok_or("--replay needs a file")?),
+            "--csv" => args.csv = Some(it.next().ok_or("--csv needs a file")?),
+            "--soil" => args.soil = true,
+            "--quiet" | "-q" => args.quiet = true,
+            "--help" | "-h" => return Err(USAGE.to_string()),
+            _ => return Err(format!("unknown arg `{}`\n{}", a, USAGE)),
+        }
+    }
+
+    if args.replay.is_some() && (args.seed.is_some() || args.settings.is_some() || args.size.is_some() || args.terrain.is_some() || args.boundary.is_some() || args.soil) {
+        return Err("--replay brings its own seed and settings".to_string());
+    }
+    Ok(args)
+}
+
+fn next_num(it: &mut impl Iterator<Item = String>, flag: &str) -> Result<u64, String> {
+    let v = it.next().ok_or(format!("{} needs a number", flag))?;
+    v.parse::<u64>().map_err(|_| format!("{}: bad number `{}`", flag, v))
+}
+
+fn main() {
+    let args = match parse_args() {
+        Ok(a) => a,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(2);
+        }
+    };
+
+    // a plain run is just a replay without events
+    let mut replay = match &args.replay {
+        Some(path) => match Replay::read(path) {
+            Ok(r) => r,
+            Err(e) => {
+                eprintln!("replay: {}: {}", path, e);
+                std::process::exit(1);
+            }
+        },
+        None => {
+            let mut set = match &args.settings {
+                Some(path) => match SimSettings::load_file(path) {
+                    Ok(s) => s,
+                    Err(e) => {
+                        eprintln!("settings: {}", e);
+                        std::process::exit(1);
+                    }
+                },
+                None => SimSettings::default(),
+            };
+            if let Some(t) = &args.terrain {
+                set.terrain = t.clone();
+            }
+            if let Some(b) = args.boundary {
+                set.boundary = b;
+            }
+            if args.soil {
+                set.soil = true;
+            }
+            if let Some((w, h)) = args.size {
+                set.world_w = w;
+                set.world_h = h;
+            }
+            // overrides go round from_text, so check them the same way
+            if let Err(e) = set.validate() {
+                eprintln!("{}", e);
+                std::process::exit(2);
+            }
+            let seed = args.seed.unwrap_or_else(|| gen_seed(num_cpus::get()));
+            Replay::new(seed, &set)
+        }
+    };
+
+    if let Some(t) = args.threads {
+        replay.set.threads = t;
+    }
+    let steps = args.steps.unwrap_or(if args.replay.is_some() { replay.end } else { 10_000 });
+
+    let tuning = SimTuning::default();
+    let mut play = Playback::new(&replay);
+    let names = play.world.species_names();
+
+    let mut rec = match &args.csv {
+        Some(path) => match CsvRecorder::create(path, &names) {
+            Ok(r) => Some(r),
+            Err(e) => {
+                eprintln!("csv: {}: {}", path, e);
+                std::process::exit(1);
+            }
+        },
+        None => None,
+    };
+
+    println!("# seed {}", replay.seed);
+    if !args.quiet {
+        let cols: Vec<String> = names.iter().map(|n| format!("{}_birth,{}_death", n, n)).collect();
+        println!("step,{}", cols.join(","));
+    }
+
+    for s in 1..=steps {
+        let seed = play.seed;
+        let d = play.step(tuning.fixed_dt);
+        if !args.quiet && play.seed != seed {
+            println!("# new seed {}", play.seed);
+        }
+        if !args.quiet {
+            let cols: Vec<String> = d.births.iter().zip(&d.deaths).map(|(b, dd)| format!("{},{}", b, dd)).collect();
+            println!("{},{}", s, cols.join(","));
+        }
+        if let Some(r) = rec.as_mut()
+            && let Err(e) = r.push(s, &play.world.counts(), &d)
+        {
+            eprintln!("csv: {}: {}", r.path(), e);
+            std::process::exit(1);
+        }
+    }
+
+    if let Some(r) = rec
+        && let Err(e) = r.finish()
+    {
+        eprintln!("csv: {}", e);
+        std::process::exit(1);
+    }
+
+    let c = play.world.counts();
+    let mut line = format!("# final step={} plants_avg={:.4}", steps, c.plants_avg);
+    for (n, p) in names.iter().zip(&c.pops) {
+        line.push_str(&format!(" {}s={}", n, p.n));
+    }
+    for (n, p) in names.iter().zip(&c.pops) {
+        line.push_str(&format!(" {}_e_avg={:.4}", n, p.e_avg));
+    }
+    println!("{}", line);
+}
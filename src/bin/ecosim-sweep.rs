@@ -0,0 +1,171 @@
+use std::io::Write;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use ecosim::config::*;
+use ecosim::par;
+use ecosim::sweep::*;
+
+const USAGE: &str = "usage: ecosim-sweep --vary KEY=FROM:TO:N|KEY=A,B,C [--vary ...] [--seeds N] [--seed BASE] [--steps N] [--burn N] [--settings FILE] [--threads N] [--runs FILE] [--out FILE]";
+
+struct Args {
+    axes: Vec<Axis>,
+    seeds: u64,
+    seed: u64,
+    steps: u64,
+    burn: Option<u64>,
+    settings: Option<String>,
+    threads: u32,
+    runs: Option<String>,
+    out: Option<String>,
+}
+
+fn parse_args() -> Result<Args, String> {
+    let mut args = Args {
+        axes: Vec::new(),
+        seeds: 4,
+        seed: 1,
+        steps: 5_000,
+        burn: None,
+        settings: None,
+        threads: 0,
+        runs: None,
+        out: None,
+    };
+
+    let mut it = std::env::args().skip(1);
+    while let Some(a) = it.next() {
+        match a.as_str() {
+            "--vary" => args.axes.push(Axis::parse(&it.next().ok_or("--vary needs KEY=FROM:TO:N")?)?),
+            "--seeds" => args.seeds = next_num(&mut it, "--seeds")?,
+            "--seed" => args.seed = next_num(&mut it, "--seed")?,
+            "--steps" => args.steps = next_num(&mut it, "--steps")?,
+            "--burn" => args.burn = Some(next_num(&mut it, "--burn")?),
+            "--settings" => args.settings = Some(it.next().ok_or("--settings needs a file")?),
+            "--threads" => args.threads = next_num(&mut it, "--threads")? as u32,
+            "--runs" => args.runs = Some(it.next().ok_or("--runs needs a file")?),
+            "--out" => args.out = Some(it.next().ok_or("--out needs a file")?),
+            "--help" | "-h" => return Err(USAGE.to_string()),
+            _ => return Err(format!("unknown arg `{}`\n{}", a, USAGE)),
+        }
+    }
+
+    if args.seeds == 0 {
+        return Err("--seeds must be at least 1".to_string());
+    }
+    Ok(args)
+}
+
+fn next_num(it: &mut impl Iterator<Item = String>, flag: &str) -> Result<u64, String> {
+    let v = it.next().ok_or(format!("{} needs a number", flag))?;
+    v.parse::<u64>().map_err(|_| format!("{}: bad number `{}`", flag, v))
+}
+
+fn fail(msg: String, code: i32) -> ! {
+    eprintln!("{}", msg);
+    std::process::exit(code);
+}
+
+fn opt(v: Option<f64>) -> String {
+    v.map(|v| format!("{:.1}", v)).unwrap_or_default()
+}
+
+fn open_out(path: &Option<String>) -> Box<dyn Write> {
+    match path {
+        Some(p) => match std::fs::File::create(p) {
+            Ok(f) => Box::new(std::io::BufWriter::new(f)),
+            Err(e) => fail(format!("{}: {}", p, e), 1),
+        },
+        None => Box::new(std::io::stdout().lock()),
+    }
+}
+
+fn main() {
+    let args = parse_args().unwrap_or_else(|e| fail(e, 2));
+
+    let base = match &args.settings {
+        Some(path) => SimSettings::load_file(path).unwrap_or_else(|e| fail(format!("settings: {}", e), 1)),
+        None => SimSettings::default(),
+    };
+    let pts = points(&base, &args.axes).unwrap_or_else(|e| fail(e, 2));
+    let names: Vec<String> = base.species.iter().map(|d| d.name.clone()).collect();
+    // the first fifth is the world settling from its random start
+    let burn = args.burn.unwrap_or(args.steps / 5).min(args.steps);
+
+    // the same seeds at every point, so differences come from the settings
+    let mut jobs: Vec<(usize, u64, Option<RunSummary>)> = Vec::new();
+    for p in 0..pts.len() {
+        for k in 0..args.seeds {
+            jobs.push((p, args.seed.wrapping_add(k), None));
+        }
+    }
+
+    let total = jobs.len();
+    eprintln!("# {} points x {} seeds, {} steps each (burn-in {})", pts.len(), args.seeds, args.steps, burn);
+
+    let done = AtomicUsize::new(0);
+    par::for_each_chunk(&mut jobs, par::threads_for(args.threads), 1, |_, chunk| {
+        for (p, seed, out) in chunk.iter_mut() {
+            *out = Some(run_one(&pts[*p].set, *seed, args.steps, burn));
+            let d = done.fetch_add(1, Ordering::Relaxed) + 1;
+            eprint!("\r# {}/{} runs", d, total);
+        }
+    });
+    eprintln!();
+
+    let keys: Vec<&str> = args.axes.iter().map(|a| a.key.as_str()).collect();
+
+    if args.runs.is_some() {
+        let mut out = open_out(&args.runs);
+        let mut cols: Vec<String> = keys.iter().map(|k| k.to_string()).collect();
+        cols.push("seed".to_string());
+        for n in &names {
+            for c in ["extinct_at", "mean", "var", "period"] {
+                cols.push(format!("{}_{}", n, c));
+            }
+        }
+        let mut res = writeln!(out, "{}", cols.join(","));
+        for (p, _, r) in &jobs {
+            let r = r.as_ref().expect("every job ran");
+            let mut row = pts[*p].values.clone();
+            row.push(r.seed.to_string());
+            for s in &r.pops {
+                row.push(s.extinct_at.map(|v| v.to_string()).unwrap_or_default());
+                row.push(format!("{:.2}", s.mean));
+                row.push(format!("{:.2}", s.var));
+                row.push(opt(s.period));
+            }
+            res = res.and_then(|_| writeln!(out, "{}", row.join(",")));
+        }
+        if let Err(e) = res.and_then(|_| out.flush()) {
+            fail(format!("runs: {}", e), 1);
+        }
+    }
+
+    let mut out = open_out(&args.out);
+    let mut cols: Vec<String> = keys.iter().map(|k| k.to_string()).collect();
+    cols.push("runs".to_string());
+    for n in &names {
+        for c in ["extinct", "extinct_step", "mean", "var", "period"] {
+            cols.push(format!("{}_{}", n, c));
+        }
+    }
+    let mut res = writeln!(out, "{}", cols.join(","));
+
+    // jobs are grouped by point, one per seed
+    for (p, group) in jobs.chunks(args.seeds as usize).enumerate() {
+        let runs: Vec<RunSummary> = group.iter().filter_map(|(_, _, r)| r.clone()).collect();
+        let mut row = pts[p].values.clone();
+        row.push(runs.len().to_string());
+        for a in aggregate(&runs) {
+            row.push(format!("{:.2}", a.extinct_frac));
+            row.push(opt(a.extinct_step));
+            row.push(format!("{:.2}", a.mean));
+            row.push(format!("{:.2}", a.var));
+            row.push(opt(a.period));
+        }
+        res = res.and_then(|_| writeln!(out, "{}", row.join(",")));
+    }
+    if let Err(e) = res.and_then(|_| out.flush()) {
+        fail(format!("out: {}", e), 1);
+    }
+}
@@ -1,131 +1,426 @@
-use macroquad::prelude::*;
+use crate::species::{self, Shape, SpeciesDef, default_species};
+use crate::terrain::TerrainSource;
+use crate::world::Boundary;
 
+// world size limits, in cells
+pub const MIN_DIM: i32 = 16;
+pub const MAX_DIM: i32 = 4096;
 
-pub const W: i32 = 120;
-pub const H: i32 = 80;
-pub const CELL: f32 = 8.0;
-
-
-pub const PANEL_W: f32 = 420.0;
-pub const PAD: f32 = 14.0;
-
-// save/load
-pub const SAVE_PATH: &str = "ecosim_save.bin";
-
-// colors
-pub const BG: Color = Color::new(0.06, 0.07, 0.10, 1.0);
-pub const PANEL_BG: Color = Color::new(0.08, 0.09, 0.13, 1.0);
-pub const GRID_BG: Color = Color::new(0.05, 0.06, 0.09, 1.0);
-pub const LINE: Color = Color::new(0.20, 0.22, 0.30, 1.0);
-
-pub const TXT: Color = Color::new(0.93, 0.94, 0.98, 1.0);
-pub const SUB: Color = Color::new(0.70, 0.72, 0.80, 1.0);
-
-pub const C_PLANT: Color = Color::new(0.20, 0.90, 0.35, 1.0);
-pub const C_HERB: Color = Color::new(0.98, 0.85, 0.15, 1.0);
-pub const C_PRED: Color = Color::new(0.95, 0.25, 0.20, 1.0);
-
-pub const C_OK: Color = Color::new(0.30, 0.90, 0.55, 1.0);
-pub const C_WARN: Color = Color::new(0.95, 0.85, 0.15, 1.0);
-pub const C_BAD: Color = Color::new(0.95, 0.25, 0.20, 1.0);
-
-#[derive(Clone, Copy)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct SimSettings {
-    pub init_herbs: u32,
-    pub init_preds: u32,
+    pub world_w: i32,
+    pub world_h: i32,
     pub plant_grow: u8,
     pub plant_spread: f32,
-    pub herb_speed: f32,
-    pub pred_speed: f32,
-    pub herb_met: f32,
-    pub pred_met: f32,
+    // cells at least spread_from dense seed a random neighbour below
+    // spread_into, adding spread_amount to it
+    pub spread_from: u8,
+    pub spread_into: u8,
+    pub spread_amount: u8,
     pub eat_radius: f32,
+    // how much a kid's genome can differ from its parent's, per trait
+    pub mutation: f32,
+    // worker threads for stepping, 0 = one per cpu. never changes results
+    pub threads: u32,
+    // "flat", "random" or an image, see terrain.rs
+    pub terrain: TerrainSource,
+    // "torus", "reflect" or "closed"
+    pub boundary: Boundary,
+    // steps per year and per day, 0 = no seasons / always day. growth
+    // swings by season_amp over the year, or follows season_grow (one
+    // multiplier per season) when that has entries. see seasons.rs
+    pub season_len: u32,
+    pub season_amp: f32,
+    pub season_grow: Vec<f32>,
+    pub day_len: u32,
+    // soil nutrients under the plants, see soil.rs. grazing takes
+    // soil_graze per unit eaten, deaths give soil_corpse back and every
+    // soil_drift steps (0 = never) cells creep back towards soil_start
+    pub soil: bool,
+    pub soil_start: u8,
+    pub soil_graze: f32,
+    pub soil_corpse: u8,
+    pub soil_drift: u32,
+    pub species: Vec<SpeciesDef>,
 }
 
 impl Default for SimSettings {
     fn default() -> Self {
         Self {
-            init_herbs: 900,
-            init_preds: 40,
+            world_w: 120,
+            world_h: 80,
             plant_grow: 5,
             plant_spread: 0.30,
-            herb_speed: 0.22,
-            pred_speed: 0.32,
-            herb_met: 0.014,
-            pred_met: 0.020,
+            spread_from: 110,
+            spread_into: 60,
+            spread_amount: 45,
             eat_radius: 0.75,
+            mutation: 0.06,
+            threads: 0,
+            terrain: TerrainSource::Flat,
+            boundary: Boundary::Torus,
+            season_len: 0,
+            season_amp: 0.6,
+            season_grow: Vec::new(),
+            day_len: 0,
+            soil: false,
+            soil_start: 128,
+            soil_graze: 0.1,
+            soil_corpse: 60,
+            soil_drift: 10,
+            species: default_species(),
         }
     }
 }
 
-#[derive(Clone, Copy)]
-pub struct SimTuning {
-    pub fixed_dt: f32,
-    pub max_steps_per_frame: u32,
+impl SimSettings {
+    // settings file: one `key = value` per line, `#` starts a comment.
+    // keys are the field names above, missing keys keep their defaults.
+    // species go in `[species.<name>]` sections with SpeciesDef keys; the
+    // first section replaces the default herb/pred pair. strings are
+    // quoted and lists look like ["plants", "herb"], so files are toml.
+    pub fn from_text(text: &str) -> Result<Self, String> {
+        Self::parse_text(text, true)
+    }
+
+    // same as from_text but skips keys it doesn't know, for reading
+    // settings written by a newer version
+    pub fn from_text_lenient(text: &str) -> Result<Self, String> {
+        Self::parse_text(text, false)
+    }
+
+    fn parse_text(text: &str, strict: bool) -> Result<Self, String> {
+        let mut set = Self::default();
+
+        // None = top level, Some(None) = a section we skip
+        let mut section: Option<Option<usize>> = None;
+        let mut own_species = false;
+
+        for (n, line) in text.lines().enumerate() {
+            let line = strip_comment(line).trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            if let Some(head) = line.strip_prefix('[') {
+                let head = head.strip_suffix(']').ok_or(format!("line {}: unclosed `[`", n + 1))?.trim();
+                match head.strip_prefix("species.") {
+                    Some(name) => {
+                        if !own_species {
+                            set.species.clear();
+                            own_species = true;
+                        }
+                        set.species.push(SpeciesDef::named(name.trim()));
+                        section = Some(Some(set.species.len() - 1));
+                    }
+                    None if !strict => section = Some(None),
+                    None => return Err(format!("line {}: unknown section `[{}]`", n + 1, head)),
+                }
+                continue;
+            }
+
+            let (k, v) = match line.split_once('=') {
+                Some(kv) => kv,
+                None => return Err(format!("line {}: expected `key = value`", n + 1)),
+            };
+            let (k, v) = (k.trim(), v.trim());
+
+            let known = match section {
+                None => set.set_key(k, v),
+                Some(Some(i)) => species_key(&mut set.species[i], k, v),
+                Some(None) => Ok(true),
+            };
+            match known {
+                Ok(true) => {}
+                Ok(false) if !strict => {}
+                Ok(false) => return Err(format!("line {}: unknown key `{}`", n + 1, k)),
+                Err(e) => return Err(format!("line {}: {}", n + 1, e)),
+            }
+        }
+
+        set.validate()?;
+        Ok(set)
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        for (k, v) in [("world_w", self.world_w), ("world_h", self.world_h)] {
+            if !(MIN_DIM..=MAX_DIM).contains(&v) {
+                return Err(format!("`{}` must be {}..={}, got {}", k, MIN_DIM, MAX_DIM, v));
+            }
+        }
+        check_range("plant_spread", self.plant_spread, 0.0, 1.0)?;
+        check_range("eat_radius", self.eat_radius, 0.0, 64.0)?;
+        check_range("mutation", self.mutation, 0.0, 4.0)?;
+        check_range("season_amp", self.season_amp, 0.0, 1.0)?;
+        if self.season_grow.len() > 12 {
+            return Err(format!("`season_grow` has at most 12 seasons, got {}", self.season_grow.len()));
+        }
+        for &g in &self.season_grow {
+            check_range("season_grow", g, 0.0, 8.0)?;
+        }
+        check_range("soil_graze", self.soil_graze, 0.0, 16.0)?;
+        species::check(&self.species)
+    }
+
+    pub fn to_text(&self) -> String {
+        let mut out = String::new();
+        for (k, v) in [
+            ("world_w", self.world_w.to_string()),
+            ("world_h", self.world_h.to_string()),
+            ("plant_grow", self.plant_grow.to_string()),
+            ("plant_spread", self.plant_spread.to_string()),
+            ("spread_from", self.spread_from.to_string()),
+            ("spread_into", self.spread_into.to_string()),
+            ("spread_amount", self.spread_amount.to_string()),
+            ("eat_radius", self.eat_radius.to_string()),
+            ("mutation", self.mutation.to_string()),
+            ("threads", self.threads.to_string()),
+            ("terrain", format!("\"{}\"", self.terrain.name())),
+            ("boundary", format!("\"{}\"", self.boundary.name())),
+            ("season_len", self.season_len.to_string()),
+            ("season_amp", self.season_amp.to_string()),
+            ("season_grow", format!("[{}]", self.season_grow.iter().map(|g| g.to_string()).collect::<Vec<_>>().join(", "))),
+            ("day_len", self.day_len.to_string()),
+            ("soil", self.soil.to_string()),
+            ("soil_start", self.soil_start.to_string()),
+            ("soil_graze", self.soil_graze.to_string()),
+            ("soil_corpse", self.soil_corpse.to_string()),
+            ("soil_drift", self.soil_drift.to_string()),
+        ] {
+            out.push_str(&format!("{} = {}\n", k, v));
+        }
+
+        for d in &self.species {
+            out.push_str(&format!("\n[species.{}]\n", d.name));
+            let eats: Vec<String> = d.eats.iter().map(|e| format!("\"{}\"", e)).collect();
+            let [r, g, b] = d.color;
+            for (k, v) in [
+                ("eats", format!("[{}]", eats.join(", "))),
+                ("init", d.init.to_string()),
+                ("init_e", d.init_e.to_string()),
+                ("init_e_var", d.init_e_var.to_string()),
+                ("speed", d.speed.to_string()),
+                ("met", d.met.to_string()),
+                ("wander", d.wander.to_string()),
+                ("flee_radius", d.flee_radius.to_string()),
+                ("flee_weight", d.flee_weight.to_string()),
+                ("chase_radius", d.chase_radius.to_string()),
+                ("bite", d.bite.to_string()),
+                ("graze_e", d.graze_e.to_string()),
+                ("kill_e", d.kill_e.to_string()),
+                ("repro_e", d.repro_e.to_string()),
+                ("repro_p", d.repro_p.to_string()),
+                ("repro_keep", d.repro_keep.to_string()),
+                ("child_e", d.child_e.to_string()),
+                ("child_spread", d.child_spread.to_string()),
+                ("respawn_below", d.respawn_below.to_string()),
+                ("respawn_p", d.respawn_p.to_string()),
+                ("respawn_n", d.respawn_n.to_string()),
+                ("respawn_e", d.respawn_e.to_string()),
+                ("night_sight", d.night_sight.to_string()),
+                ("night_speed", d.night_speed.to_string()),
+                ("color", format!("\"#{:02x}{:02x}{:02x}\"", r, g, b)),
+                ("shape", format!("\"{}\"", d.shape.name())),
+            ] {
+                out.push_str(&format!("{} = {}\n", k, v));
+            }
+        }
+        out
+    }
+
+    pub fn load_file(path: &str) -> Result<Self, String> {
+        let text = std::fs::read_to_string(path).map_err(|e| format!("{}: {}", path, e))?;
+        Self::from_text(&text).map_err(|e| format!("{}: {}", path, e))
+    }
+
+    // one key by name, `name.key` for a species. doesn't validate, callers
+    // changing several keys check once at the end
+    pub fn set_value(&mut self, k: &str, v: &str) -> Result<(), String> {
+        let known = match k.split_once('.') {
+            Some((name, key)) => {
+                let i = self.species_index(name).ok_or(format!("no species named `{}`", name))?;
+                species_key(&mut self.species[i], key, v)?
+            }
+            None => self.set_key(k, v)?,
+        };
+        if known { Ok(()) } else { Err(format!("unknown key `{}`", k)) }
+    }
+
+    pub fn species_index(&self, name: &str) -> Option<usize> {
+        self.species.iter().position(|d| d.name == name)
+    }
+
+    // Ok(false) means the key isn't a setting
+    fn set_key(&mut self, k: &str, v: &str) -> Result<bool, String> {
+        match k {
+            "world_w" => self.world_w = parse_val(k, v)?,
+            "world_h" => self.world_h = parse_val(k, v)?,
+            "plant_grow" => self.plant_grow = parse_val(k, v)?,
+            "plant_spread" => self.plant_spread = parse_val(k, v)?,
+            "spread_from" => self.spread_from = parse_val(k, v)?,
+            "spread_into" => self.spread_into = parse_val(k, v)?,
+            "spread_amount" => self.spread_amount = parse_val(k, v)?,
+            "eat_radius" => self.eat_radius = parse_val(k, v)?,
+            "mutation" => self.mutation = parse_val(k, v)?,
+            "threads" => self.threads = parse_val(k, v)?,
+            "terrain" => self.terrain = TerrainSource::parse(&parse_word(k, v)?)?,
+            "season_len" => self.season_len = parse_val(k, v)?,
+            "season_amp" => self.season_amp = parse_val(k, v)?,
+            "season_grow" => self.season_grow = parse_nums(k, v)?,
+            "day_len" => self.day_len = parse_val(k, v)?,
+            "soil" => self.soil = parse_val(k, v)?,
+            "soil_start" => self.soil_start = parse_val(k, v)?,
+            "soil_graze" => self.soil_graze = parse_val(k, v)?,
+            "soil_corpse" => self.soil_corpse = parse_val(k, v)?,
+            "soil_drift" => self.soil_drift = parse_val(k, v)?,
+            "boundary" => {
+                let s = parse_word(k, v)?;
+                self.boundary = Boundary::parse(&s).ok_or(format!("bad boundary `{}`, expected torus/reflect/closed", s))?;
+            }
+            // from before species were configurable, still found in old saves
+            "init_herbs" | "init_preds" | "herb_speed" | "pred_speed" | "herb_met" | "pred_met" => {
+                let (name, field) = k.split_once('_').unwrap_or((k, ""));
+                let name = match name {
+                    "init" => field.trim_end_matches('s'),
+                    n => n,
+                };
+                let i = self.species_index(name).ok_or(format!("`{}` needs a species named `{}`", k, name))?;
+                let d = &mut self.species[i];
+                match field {
+                    "speed" => d.speed = parse_val(k, v)?,
+                    "met" => d.met = parse_val(k, v)?,
+                    _ => d.init = parse_val(k, v)?,
+                }
+            }
+            _ => return Ok(false),
+        }
+        Ok(true)
+    }
 }
 
-impl Default for SimTuning {
-    fn default() -> Self {
-        Self {
-            fixed_dt: 1.0 / 60.0,
-            max_steps_per_frame: 8,
+fn species_key(d: &mut SpeciesDef, k: &str, v: &str) -> Result<bool, String> {
+    match k {
+        "eats" => d.eats = parse_list(k, v)?,
+        "init" => d.init = parse_val(k, v)?,
+        "init_e" => d.init_e = parse_val(k, v)?,
+        "init_e_var" => d.init_e_var = parse_val(k, v)?,
+        "speed" => d.speed = parse_val(k, v)?,
+        "met" => d.met = parse_val(k, v)?,
+        "wander" => d.wander = parse_val(k, v)?,
+        "flee_radius" => d.flee_radius = parse_val(k, v)?,
+        "flee_weight" => d.flee_weight = parse_val(k, v)?,
+        "chase_radius" => d.chase_radius = parse_val(k, v)?,
+        "bite" => d.bite = parse_val(k, v)?,
+        "graze_e" => d.graze_e = parse_val(k, v)?,
+        "kill_e" => d.kill_e = parse_val(k, v)?,
+        "repro_e" => d.repro_e = parse_val(k, v)?,
+        "repro_p" => d.repro_p = parse_val(k, v)?,
+        "repro_keep" => d.repro_keep = parse_val(k, v)?,
+        "child_e" => d.child_e = parse_val(k, v)?,
+        "child_spread" => d.child_spread = parse_val(k, v)?,
+        "respawn_below" => d.respawn_below = parse_val(k, v)?,
+        "respawn_p" => d.respawn_p = parse_val(k, v)?,
+        "respawn_n" => d.respawn_n = parse_val(k, v)?,
+        "respawn_e" => d.respawn_e = parse_val(k, v)?,
+        "night_sight" => d.night_sight = parse_val(k, v)?,
+        "night_speed" => d.night_speed = parse_val(k, v)?,
+        "color" => d.color = parse_color(k, v)?,
+        "shape" => {
+            let s = parse_str(k, v)?;
+            d.shape = Shape::parse(&s).ok_or(format!("bad shape `{}`, expected circle/triangle/square/diamond", s))?;
         }
+        _ => return Ok(false),
     }
+    Ok(true)
 }
 
-#[derive(Clone, Copy)]
-pub enum SimMode {
-    Home,
-    Running,
-    Paused,
+// `#` only starts a comment outside of quotes, colors use it too
+fn strip_comment(line: &str) -> &str {
+    let mut quoted = false;
+    for (i, c) in line.char_indices() {
+        match c {
+            '"' => quoted = !quoted,
+            '#' if !quoted => return &line[..i],
+            _ => {}
+        }
+    }
+    line
 }
 
-pub struct Layout {
-    pub world_w_px: f32,
-    pub world_h_px: f32,
-    pub panel_x: f32,
-    pub panel_w: f32,
+fn parse_str(k: &str, v: &str) -> Result<String, String> {
+    let s = v.strip_prefix('"').and_then(|s| s.strip_suffix('"'));
+    s.map(|s| s.to_string()).ok_or(format!("`{}` needs a quoted string, got `{}`", k, v))
 }
 
-impl Layout {
-    pub fn compute(sw: f32, sh: f32) -> Self {
-        let panel_w = PANEL_W;
-        let world_w = (sw - panel_w).max(320.0);
-        Self {
-            world_w_px: world_w,
-            world_h_px: sh,
-            panel_x: world_w,
-            panel_w,
-        }
+// a quoted string, or a bare word so sweeps can --vary boundary=torus,closed
+fn parse_word(k: &str, v: &str) -> Result<String, String> {
+    if v.starts_with('"') { parse_str(k, v) } else { Ok(v.to_string()) }
+}
+
+// ["a", "b"]
+fn parse_list(k: &str, v: &str) -> Result<Vec<String>, String> {
+    let inner = v.strip_prefix('[').and_then(|s| s.strip_suffix(']'));
+    let inner = inner.ok_or(format!("`{}` needs a list like [\"a\", \"b\"], got `{}`", k, v))?;
+    inner
+        .split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(|s| parse_str(k, s))
+        .collect()
+}
+
+// [1.5, 0.8]
+fn parse_nums(k: &str, v: &str) -> Result<Vec<f32>, String> {
+    let inner = v.strip_prefix('[').and_then(|s| s.strip_suffix(']'));
+    let inner = inner.ok_or(format!("`{}` needs a list like [1.5, 0.8], got `{}`", k, v))?;
+    inner.split(',').map(str::trim).filter(|s| !s.is_empty()).map(|s| parse_val(k, s)).collect()
+}
+
+// "#rrggbb"
+fn parse_color(k: &str, v: &str) -> Result<[u8; 3], String> {
+    let s = parse_str(k, v)?;
+    let bad = || format!("`{}` needs a color like \"#ffcc00\", got `{}`", k, v);
+    let hex = s.strip_prefix('#').filter(|h| h.len() == 6).ok_or_else(bad)?;
+    let mut out = [0u8; 3];
+    for (i, c) in out.iter_mut().enumerate() {
+        *c = u8::from_str_radix(&hex[i * 2..i * 2 + 2], 16).map_err(|_| bad())?;
     }
+    Ok(out)
 }
 
-#[derive(Clone, Copy)]
-pub struct Counts {
-    pub plants_avg: f32,
-    pub herbs: u32,
-    pub preds: u32,
-    pub herb_e_avg: f32,
-    pub pred_e_avg: f32,
+// also rejects nan
+pub fn check_range(k: &str, v: f32, lo: f32, hi: f32) -> Result<(), String> {
+    if (lo..=hi).contains(&v) {
+        Ok(())
+    } else {
+        Err(format!("`{}` must be {}..={}, got {}", k, lo, hi, v))
+    }
 }
 
-#[derive(Clone, Copy)]
-pub struct Deltas {
-    pub herb_birth: u32,
-    pub herb_death: u32,
-    pub pred_birth: u32,
-    pub pred_death: u32,
+// "200x150" -> (200, 150)
+pub fn parse_size(s: &str) -> Result<(i32, i32), String> {
+    let bad = || format!("bad size `{}`, expected WxH like 200x150", s);
+    let (w, h) = s.split_once(['x', 'X']).ok_or_else(bad)?;
+    let w = w.trim().parse::<i32>().map_err(|_| bad())?;
+    let h = h.trim().parse::<i32>().map_err(|_| bad())?;
+    Ok((w, h))
 }
 
+fn parse_val<T: std::str::FromStr>(k: &str, v: &str) -> Result<T, String> {
+    v.parse::<T>().map_err(|_| format!("bad value `{}` for `{}`", v, k))
+}
 
-#[derive(Clone, Copy, PartialEq, Eq)]
-pub enum TrackKind {
-    Herb,
-    Pred,
+#[derive(Clone, Copy)]
+pub struct SimTuning {
+    pub fixed_dt: f32,
+    pub max_steps_per_frame: u32,
 }
 
-#[derive(Clone, Copy, PartialEq, Eq)]
-pub struct TrackTarget {
-    pub kind: TrackKind,
-    pub id: u32,
+impl Default for SimTuning {
+    fn default() -> Self {
+        Self {
+            fixed_dt: 1.0 / 60.0,
+            max_steps_per_frame: 8,
+        }
+    }
 }
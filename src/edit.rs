@@ -0,0 +1,103 @@
+// hand edits to a running world: painting plants, dropping agents in,
+// taking them out. they go into replays like everything else the user
+// does, so none of them draw from the rng.
+
+use crate::genome::Genome;
+use crate::vec2::{Vec2, vec2};
+use crate::world::{Agent, LifeEvent, TrackTarget, World};
+
+// brush sizes and spawn energy are kept to this, anything past it is a typo
+pub const MAX_BRUSH: f32 = 64.0;
+pub const MAX_SPAWN_E: f32 = 1000.0;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Edit {
+    // adds `amount` to the plant density of every cell within r of at,
+    // negative erases. water and rock stay bare
+    Plants { at: Vec2, r: f32, amount: i32 },
+    // one agent with the base genome and no parent, not into rock
+    Spawn { species: usize, at: Vec2, e: f32 },
+    // every agent within r of at
+    Remove { at: Vec2, r: f32 },
+}
+
+// applies an edit, says what it did for the event log
+pub fn apply(world: &mut World, ed: Edit) -> String {
+    match ed {
+        Edit::Plants { at, r, amount } => {
+            let n = paint(world, at, r.clamp(0.0, MAX_BRUSH), amount.clamp(-255, 255));
+            format!("{} plants on {} cells", if amount >= 0 { "painted" } else { "erased" }, n)
+        }
+        Edit::Spawn { species, at, e } => {
+            let Some(name) = world.species.get(species).map(|s| s.def.name.clone()) else {
+                return format!("no species {} to spawn", species);
+            };
+            if world.ground[world.space.cell_of(at)].blocks() {
+                return "can't spawn into rock".to_string();
+            }
+            let id = spawn(world, species, at, if e.is_finite() { e.clamp(0.01, MAX_SPAWN_E) } else { 1.0 });
+            format!("spawned {} #{}", name, id)
+        }
+        Edit::Remove { at, r } => {
+            let n = remove(world, at, r.clamp(0.0, MAX_BRUSH));
+            format!("removed {} agents", n)
+        }
+    }
+}
+
+// growable cells whose centres are within r, across the wrap or up to
+// the walls. returns how many
+fn paint(world: &mut World, at: Vec2, r: f32, amount: i32) -> usize {
+    let sp = world.space;
+    let ri = r.ceil() as i32;
+    let (cx, cy) = (at.x.floor() as i32, at.y.floor() as i32);
+    let mut n = 0;
+    for dy in -ri..=ri {
+        for dx in -ri..=ri {
+            let c = vec2((cx + dx) as f32 + 0.5, (cy + dy) as f32 + 0.5);
+            if sp.dist(at, c) > r.max(0.5) {
+                continue;
+            }
+            let Some(i) = sp.cell(cx + dx, cy + dy) else {
+                continue;
+            };
+            if !world.ground[i].grows() {
+                continue;
+            }
+            world.plants[i] = (world.plants[i] as i32 + amount).clamp(0, 255) as u8;
+            n += 1;
+        }
+    }
+    n
+}
+
+fn spawn(world: &mut World, species: usize, at: Vec2, e: f32) -> u32 {
+    let p = world.space.wrap(at);
+    let id = world.next_id;
+    world.next_id = world.next_id.saturating_add(1);
+    let now = world.now;
+    world.pops[species].push(Agent { id, p, pp: p, e, age: 0, g: Genome::BASE, parent: 0, born: now });
+    world.lineage.born(id, 0, species, now);
+    id
+}
+
+fn remove(world: &mut World, at: Vec2, r: f32) -> usize {
+    let sp = world.space;
+    let now = world.now;
+    let watch = world.watch;
+    let mut n = 0;
+    for (s, list) in world.pops.iter_mut().enumerate() {
+        list.retain(|a| {
+            if sp.dist(at, a.p) > r {
+                return true;
+            }
+            world.lineage.died(a.id, now);
+            if watch == Some(TrackTarget { species: s, id: a.id }) {
+                world.life.push(LifeEvent::Removed);
+            }
+            n += 1;
+            false
+        });
+    }
+    n
+}
@@ -0,0 +1,71 @@
+use rand09::Rng;
+
+// per-agent heritable traits. all of them are multipliers on the species
+// baseline from the settings, so 1.0 everywhere is a "stock" agent and old
+// saves without genomes load as exactly that.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Genome {
+    // move speed
+    pub speed: f32,
+    // how far it notices others (herbs: predators, preds: prey)
+    pub sense: f32,
+    // energy needed before it reproduces
+    pub repro: f32,
+    // herbs: how hard they flee, preds: how hard they commit to a chase
+    pub drive: f32,
+}
+
+pub const TRAIT_COUNT: usize = 4;
+pub const TRAIT_NAMES: [&str; TRAIT_COUNT] = ["speed", "sense", "repro", "drive"];
+
+// how far a trait can drift from the baseline either way
+const TRAIT_MIN: f32 = 0.25;
+const TRAIT_MAX: f32 = 4.0;
+
+impl Genome {
+    pub const BASE: Genome = Genome { speed: 1.0, sense: 1.0, repro: 1.0, drive: 1.0 };
+
+    pub fn get(&self, i: usize) -> f32 {
+        match i {
+            0 => self.speed,
+            1 => self.sense,
+            2 => self.repro,
+            _ => self.drive,
+        }
+    }
+
+    fn set(&mut self, i: usize, v: f32) {
+        match i {
+            0 => self.speed = v,
+            1 => self.sense = v,
+            2 => self.repro = v,
+            _ => self.drive = v,
+        }
+    }
+
+    // each trait gets nudged by up to +-rate (triangular, so small changes
+    // are the common case). rate 0 means clones.
+    pub fn mutate(&self, rng: &mut impl Rng, rate: f32) -> Genome {
+        let mut g = *self;
+        if rate <= 0.0 {
+            return g;
+        }
+        for i in 0..TRAIT_COUNT {
+            let k = 1.0 + (rng.random::<f32>() - rng.random::<f32>()) * rate;
+            g.set(i, (g.get(i) * k).clamp(TRAIT_MIN, TRAIT_MAX));
+        }
+        g
+    }
+
+    // metabolism multiplier. speed costs quadratically, sensing a bit,
+    // so nothing gets to be fast and far-sighted for free.
+    pub fn upkeep(&self) -> f32 {
+        (0.4 + 0.6 * self.speed * self.speed) * (0.85 + 0.15 * self.sense)
+    }
+}
+
+impl Default for Genome {
+    fn default() -> Self {
+        Self::BASE
+    }
+}
@@ -0,0 +1,76 @@
+use macroquad::prelude::*;
+
+use ecosim::species::SpeciesDef;
+
+
+pub const PANEL_W: f32 = 420.0;
+pub const PAD: f32 = 14.0;
+
+// save/load
+pub const SAVE_DIR: &str = "saves";
+// where the single save slot used to live, still listed in the browser
+pub const SAVE_PATH: &str = "ecosim_save.bin";
+// home screen o / w, unless --settings points somewhere else
+pub const SETTINGS_FILE: &str = "ecosim.toml";
+
+// rewind: a snapshot every so many steps, the last so many kept.
+// 120 x 100 steps is the last 12k steps
+pub const REWIND_EVERY: u64 = 100;
+pub const REWIND_KEEP: usize = 120;
+// timeline strip under the graphs
+pub const SCRUB_H: f32 = 30.0;
+
+// colors
+pub const BG: Color = Color::new(0.06, 0.07, 0.10, 1.0);
+pub const PANEL_BG: Color = Color::new(0.08, 0.09, 0.13, 1.0);
+pub const GRID_BG: Color = Color::new(0.05, 0.06, 0.09, 1.0);
+pub const LINE: Color = Color::new(0.20, 0.22, 0.30, 1.0);
+
+pub const TXT: Color = Color::new(0.93, 0.94, 0.98, 1.0);
+pub const SUB: Color = Color::new(0.70, 0.72, 0.80, 1.0);
+
+pub const C_PLANT: Color = Color::new(0.20, 0.90, 0.35, 1.0);
+// full soil in the soil view (m), empty soil is the bare ground colour
+pub const C_SOIL: Color = Color::new(0.85, 0.52, 0.16, 1.0);
+
+// bare ground under the plants, plain is GRID_BG
+pub const G_FERTILE: Color = Color::new(0.09, 0.08, 0.05, 1.0);
+pub const G_BARREN: Color = Color::new(0.17, 0.14, 0.09, 1.0);
+pub const G_WATER: Color = Color::new(0.07, 0.16, 0.32, 1.0);
+pub const G_ROCK: Color = Color::new(0.28, 0.28, 0.30, 1.0);
+
+pub const C_OK: Color = Color::new(0.30, 0.90, 0.55, 1.0);
+pub const C_WARN: Color = Color::new(0.95, 0.85, 0.15, 1.0);
+pub const C_BAD: Color = Color::new(0.95, 0.25, 0.20, 1.0);
+
+#[derive(Clone, Copy)]
+pub enum SimMode {
+    Home,
+    Running,
+    Paused,
+}
+
+pub struct Layout {
+    pub world_w_px: f32,
+    pub world_h_px: f32,
+    pub panel_x: f32,
+    pub panel_w: f32,
+}
+
+impl Layout {
+    pub fn compute(sw: f32, sh: f32) -> Self {
+        let panel_w = PANEL_W;
+        let world_w = (sw - panel_w).max(320.0);
+        Self {
+            world_w_px: world_w,
+            world_h_px: sh,
+            panel_x: world_w,
+            panel_w,
+        }
+    }
+}
+
+pub fn species_color(d: &SpeciesDef) -> Color {
+    let [r, g, b] = d.color;
+    Color::from_rgba(r, g, b, 255)
+}
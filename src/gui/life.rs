@@ -0,0 +1,97 @@
+use std::collections::VecDeque;
+
+use ecosim::world::{LifeEvent, TrackTarget, World};
+
+const ENERGY_CAP: usize = 2000;
+const LOG_CAP: usize = 40;
+// near misses by the same species this close together are one scare
+const SCARE_GAP: u64 = 10;
+
+// one line of the tracked agent's story. grazing and near misses come
+// every step while they last, so runs of them fold into one line
+pub struct LifeEntry {
+    pub step: u64,
+    pub ev: LifeEvent,
+    // steps folded in
+    pub n: u32,
+}
+
+pub struct Death {
+    pub step: u64,
+    pub cause: LifeEvent,
+    pub age: u32,
+}
+
+// what the tracked agent has been up to since it was picked
+pub struct LifeStory {
+    pub target: Option<TrackTarget>,
+    // energy after every step, oldest first
+    pub energy: VecDeque<f32>,
+    // newest first
+    pub log: VecDeque<LifeEntry>,
+    pub meals: u32,
+    pub kids: u32,
+    pub age: u32,
+    pub death: Option<Death>,
+}
+
+impl LifeStory {
+    pub fn new(target: Option<TrackTarget>) -> Self {
+        Self { target, energy: VecDeque::new(), log: VecDeque::new(), meals: 0, kids: 0, age: 0, death: None }
+    }
+
+    // call after every step
+    pub fn update(&mut self, steps: u64, world: &mut World) {
+        self.take_events(steps, world);
+        if let Some(t) = self.target.and_then(|t| world.tracked_info(t)) {
+            self.age = t.age;
+            self.energy.push_back(t.e);
+            if self.energy.len() > ENERGY_CAP {
+                self.energy.pop_front();
+            }
+        }
+    }
+
+    // also after edits, which can happen while paused
+    pub fn take_events(&mut self, steps: u64, world: &mut World) {
+        for ev in world.take_life_events() {
+            self.add(steps, ev);
+        }
+    }
+
+    fn add(&mut self, step: u64, ev: LifeEvent) {
+        match ev {
+            LifeEvent::Ate(_) => self.meals += 1,
+            LifeEvent::Bred(_) => self.kids += 1,
+            LifeEvent::Removed => self.death = Some(Death { step, cause: ev, age: self.age }),
+            LifeEvent::Starved | LifeEvent::Eaten(_) => {
+                // it died during this step, a step older than last seen
+                self.death = Some(Death { step, cause: ev, age: self.age + 1 });
+            }
+            _ => {}
+        }
+
+        if let Some(last) = self.log.front_mut() {
+            let fold = match (last.ev, ev) {
+                (LifeEvent::Grazed(a), LifeEvent::Grazed(b)) if step <= last.step + 1 => Some(LifeEvent::Grazed(a + b)),
+                (LifeEvent::NearMiss(a), LifeEvent::NearMiss(b)) if a == b && step <= last.step + SCARE_GAP => Some(ev),
+                _ => None,
+            };
+            if let Some(f) = fold {
+                last.ev = f;
+                last.step = step;
+                last.n += 1;
+                return;
+            }
+        }
+
+        // a grazing run counts as one meal once it starts
+        if let LifeEvent::Grazed(_) = ev {
+            self.meals += 1;
+        }
+        self.log.push_front(LifeEntry { step, ev, n: 1 });
+        if self.log.len() > LOG_CAP {
+            self.log.pop_back();
+        }
+    }
+}
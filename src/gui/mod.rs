@@ -0,0 +1,7 @@
+pub mod config;
+pub mod life;
+pub mod playback;
+pub mod render;
+pub mod saves;
+pub mod tools;
+pub mod ui;
@@ -0,0 +1,41 @@
+use ecosim::replay::{Event, Replay};
+
+// feeds a replay's events back into the gui loop at the steps they were
+// recorded at. while paused it also waits out the recorded wall time, so
+// pauses last about as long as they did
+pub struct Player {
+    pub replay: Replay,
+    next: usize,
+    // recorded ms of the last event and when it fired here
+    last_ms: u64,
+    last_at: f64,
+}
+
+impl Player {
+    pub fn new(replay: Replay, now: f64) -> Self {
+        Self { replay, next: 0, last_ms: 0, last_at: now }
+    }
+
+    // stepping has to stop here until the events for this tick are out
+    pub fn blocks(&self, tick: u64) -> bool {
+        self.done(tick) || self.replay.events.get(self.next).is_some_and(|t| t.tick <= tick)
+    }
+
+    pub fn poll(&mut self, tick: u64, paused: bool, now: f64) -> Option<Event> {
+        let t = *self.replay.events.get(self.next)?;
+        if t.tick > tick {
+            return None;
+        }
+        if paused && now - self.last_at < t.ms.saturating_sub(self.last_ms) as f64 / 1000.0 {
+            return None;
+        }
+        self.next += 1;
+        self.last_ms = t.ms;
+        self.last_at = now;
+        Some(t.ev)
+    }
+
+    pub fn done(&self, tick: u64) -> bool {
+        tick >= self.replay.end && self.next >= self.replay.events.len()
+    }
+}
@@ -0,0 +1,377 @@
+use std::collections::{HashSet, VecDeque};
+
+use macroquad::prelude::*;
+
+use ecosim::util::lerp;
+use ecosim::species::Shape;
+use ecosim::terrain::Ground;
+use ecosim::vec2::Vec2 as WPos;
+use ecosim::world::{Space, TrackTarget, TrackedInfo, World, interp_agent};
+
+use super::config::*;
+
+// draws the world through a camera. terrain and plants go through a
+// texture so big worlds don't cost one rectangle per cell. on a torus
+// panning past an edge shows the other side, walled worlds just end
+pub struct WorldView {
+    img: Image,
+    tex: Option<Texture2D>,
+    // world point at the middle of the view, and zoom over the scale that
+    // fits the whole world
+    center: Vec2,
+    zoom: f32,
+    // last mouse position while dragging
+    drag: Option<Vec2>,
+    // the size the camera was fit to, a different world gets fit again
+    fit_for: Option<Space>,
+    // keep the tracked agent in the middle
+    pub follow: bool,
+    // family tree of the tracked agent, its living descendants ringed
+    pub family: bool,
+    // soil nutrients instead of plants
+    pub soil: bool,
+    // where the tracked agent has been, newest last
+    trail: VecDeque<WPos>,
+    trail_of: Option<TrackTarget>,
+}
+
+const ZOOM_MAX: f32 = 48.0;
+const ZOOM_STEP: f32 = 1.15;
+const TRAIL_LEN: usize = 240;
+
+impl WorldView {
+    pub fn new() -> Self {
+        Self {
+            img: Image::empty(),
+            tex: None,
+            center: Vec2::ZERO,
+            zoom: 1.0,
+            drag: None,
+            fit_for: None,
+            follow: false,
+            family: false,
+            soil: false,
+            trail: VecDeque::new(),
+            trail_of: None,
+        }
+    }
+
+    // pixels per world cell at zoom 1
+    fn fit_px(layout: &Layout, sp: Space) -> f32 {
+        (layout.world_w_px / sp.w as f32).min(layout.world_h_px / sp.h as f32)
+    }
+
+    pub fn cell_px(&self, layout: &Layout, sp: Space) -> f32 {
+        Self::fit_px(layout, sp) * self.zoom
+    }
+
+    pub fn fit(&mut self, sp: Space) {
+        self.center = vec2(sp.w as f32 * 0.5, sp.h as f32 * 0.5);
+        self.zoom = 1.0;
+        self.fit_for = Some(sp);
+        self.follow = false;
+    }
+
+    // screen position of world (0, 0), the copy nearest the middle of the view
+    fn origin(&self, layout: &Layout, sp: Space) -> Vec2 {
+        let c = self.cell_px(layout, sp);
+        vec2(layout.world_w_px * 0.5 - self.center.x * c, layout.world_h_px * 0.5 - self.center.y * c)
+    }
+
+    pub fn screen_to_world(&self, layout: &Layout, sp: Space, mx: f32, my: f32) -> Option<ecosim::vec2::Vec2> {
+        if mx < 0.0 || my < 0.0 || mx >= layout.world_w_px || my >= layout.world_h_px {
+            return None;
+        }
+        let c = self.cell_px(layout, sp);
+        let o = self.origin(layout, sp);
+        let p = ecosim::vec2::vec2((mx - o.x) / c, (my - o.y) / c);
+        if sp.wraps() {
+            Some(sp.wrap(p))
+        } else {
+            (p.x >= 0.0 && p.y >= 0.0 && p.x < sp.w as f32 && p.y < sp.h as f32).then_some(p)
+        }
+    }
+
+    // wheel zooms around the mouse, right or middle drag pans, f fits the
+    // world, g follows the tracked agent until the next pan, k shows its family
+    pub fn input(&mut self, layout: &Layout, sp: Space) {
+        if self.fit_for != Some(sp) {
+            self.fit(sp);
+        }
+        if is_key_pressed(KeyCode::F) {
+            self.fit(sp);
+        }
+        if is_key_pressed(KeyCode::G) {
+            self.follow = !self.follow;
+        }
+        if is_key_pressed(KeyCode::K) {
+            self.family = !self.family;
+        }
+        if is_key_pressed(KeyCode::M) {
+            self.soil = !self.soil;
+        }
+
+        let (mx, my) = mouse_position();
+        let m = vec2(mx, my);
+        let over = mx >= 0.0 && mx < layout.world_w_px && my >= 0.0 && my < layout.world_h_px;
+
+        let (_, wheel) = mouse_wheel();
+        if over && wheel != 0.0 {
+            let c0 = self.cell_px(layout, sp);
+            let at = self.center + (m - vec2(layout.world_w_px, layout.world_h_px) * 0.5) / c0;
+            let z = if wheel > 0.0 { self.zoom * ZOOM_STEP } else { self.zoom / ZOOM_STEP };
+            self.zoom = z.clamp(1.0, ZOOM_MAX);
+            // keep the point under the mouse where it was
+            let c1 = self.cell_px(layout, sp);
+            self.center = at - (m - vec2(layout.world_w_px, layout.world_h_px) * 0.5) / c1;
+        }
+
+        let held = is_mouse_button_down(MouseButton::Right) || is_mouse_button_down(MouseButton::Middle);
+        match self.drag {
+            Some(last) if held => {
+                if m != last {
+                    self.follow = false;
+                }
+                self.center -= (m - last) / self.cell_px(layout, sp);
+                self.drag = Some(m);
+            }
+            None if held && over => self.drag = Some(m),
+            _ if !held => self.drag = None,
+            _ => {}
+        }
+
+        let w = vec2(sp.w as f32, sp.h as f32);
+        self.center = if sp.wraps() {
+            vec2(self.center.x.rem_euclid(w.x), self.center.y.rem_euclid(w.y))
+        } else {
+            self.center.clamp(Vec2::ZERO, w)
+        };
+    }
+
+    // copies of the world that reach into the view, as screen offsets of
+    // their origin. one at fit, a few when panned across an edge of a torus
+    fn tiles(&self, layout: &Layout, sp: Space) -> Vec<Vec2> {
+        let c = self.cell_px(layout, sp);
+        let o = self.origin(layout, sp);
+        if !sp.wraps() {
+            return vec![o];
+        }
+        let (tw, th) = (sp.w as f32 * c, sp.h as f32 * c);
+        let xs = (-o.x / tw).floor() as i32..=((layout.world_w_px - o.x) / tw).floor() as i32;
+        let ys = (-o.y / th).floor() as i32..=((layout.world_h_px - o.y) / th).floor() as i32;
+        let mut out = Vec::new();
+        for ty in ys {
+            for tx in xs.clone() {
+                out.push(vec2(o.x + tx as f32 * tw, o.y + ty as f32 * th));
+            }
+        }
+        out
+    }
+
+    fn upload_plants(&mut self, world: &World) {
+        let sp = world.space();
+        let (w, h) = (sp.w as u16, sp.h as u16);
+
+        if self.img.width != w || self.img.height != h {
+            self.img = Image::gen_image_color(w, h, GRID_BG);
+            self.tex = None;
+        }
+
+        let (layer, top) = if self.soil { (world.soil(), C_SOIL) } else { (world.plants(), C_PLANT) };
+        for (i, (&pv, &g)) in layer.iter().zip(world.ground()).enumerate() {
+            let v = pv as f32 / 255.0;
+            let bg = ground_color(g);
+            // water and rock hold no soil worth showing
+            let col = if v <= 0.01 || !g.grows() {
+                bg
+            } else {
+                Color::new(lerp(bg.r, top.r, v), lerp(bg.g, top.g, v), lerp(bg.b, top.b, v), 1.0)
+            };
+            let px: [u8; 4] = col.into();
+            self.img.bytes[i * 4..i * 4 + 4].copy_from_slice(&px);
+        }
+
+        match &self.tex {
+            Some(t) => t.update(&self.img),
+            None => {
+                let t = Texture2D::from_image(&self.img);
+                t.set_filter(FilterMode::Nearest);
+                self.tex = Some(t);
+            }
+        }
+    }
+
+    pub fn draw(&mut self, world: &World, layout: &Layout, alpha: f32, tracked: Option<TrackTarget>) {
+        let sp = world.space();
+        if self.fit_for != Some(sp) {
+            self.fit(sp);
+        }
+
+        let info = tracked.and_then(|t| world.tracked_info(t));
+        let focus = tracked
+            .and_then(|t| world.agents(t.species).iter().find(|a| a.id == t.id))
+            .map(|a| interp_agent(sp, a, alpha));
+        self.update_trail(sp, tracked, info.as_ref());
+        if self.follow
+            && let Some(f) = focus
+        {
+            self.center = vec2(f.x + 0.5, f.y + 0.5);
+        }
+
+        let cell = self.cell_px(layout, sp);
+        let tiles = self.tiles(layout, sp);
+        let (vw, vh) = (layout.world_w_px, layout.world_h_px);
+
+        // past the walls of a bounded world is nothing
+        draw_rectangle(0.0, 0.0, vw, vh, if sp.wraps() { GRID_BG } else { BG });
+
+        self.upload_plants(world);
+        let size = vec2(sp.w as f32 * cell, sp.h as f32 * cell);
+        if let Some(t) = &self.tex {
+            for o in &tiles {
+                draw_texture_ex(t, o.x, o.y, WHITE, DrawTextureParams { dest_size: Some(size), ..Default::default() });
+            }
+        }
+        // seams between copies, or the walls
+        let (edge_w, edge_col) = if sp.wraps() { (1.0, LINE) } else { (3.0, SUB) };
+        for o in &tiles {
+            draw_rectangle_lines(o.x, o.y, size.x, size.y, edge_w, edge_col);
+        }
+
+        let mut tracked_px: Vec<Vec2> = Vec::new();
+        let mut kin_px: Vec<Vec2> = Vec::new();
+        let kin: HashSet<u32> = match tracked {
+            Some(t) if self.family => world.lineage().descendants(t.id).into_iter().collect(),
+            _ => HashSet::new(),
+        };
+
+        for (s, spc) in world.species().iter().enumerate() {
+            let col = species_color(&spc.def);
+            // keep agents visible once cells get smaller than a pixel or two
+            let r = match spc.def.shape {
+                Shape::Circle => (cell * 0.42).max(1.2),
+                _ => (cell * 0.55).max(1.6),
+            };
+
+            for a in world.agents(s) {
+                let p = interp_agent(sp, a, alpha);
+                let is_tracked = tracked == Some(TrackTarget { species: s, id: a.id });
+                for o in &tiles {
+                    let px = o.x + p.x * cell + cell * 0.5;
+                    let py = o.y + p.y * cell + cell * 0.5;
+                    if px < -r || py < -r || px > vw + r || py > vh + r {
+                        continue;
+                    }
+                    draw_shape(spc.def.shape, px, py, r, col);
+                    if is_tracked {
+                        tracked_px.push(vec2(px, py));
+                    } else if kin.contains(&a.id) {
+                        kin_px.push(vec2(px, py));
+                    }
+                }
+            }
+        }
+
+        if let (Some(t), Some(f)) = (&info, focus) {
+            for &tp in &tracked_px {
+                self.draw_tracked_extras(sp, cell, tp, f, t);
+            }
+        }
+
+        let r = cell.max(6.0);
+        for kp in kin_px {
+            draw_circle_lines(kp.x, kp.y, r * 0.7, 1.5, Color::new(C_WARN.r, C_WARN.g, C_WARN.b, 0.85));
+        }
+        for tp in tracked_px {
+            draw_circle_lines(tp.x, tp.y, r * 0.78, 3.0, Color::new(0.95, 0.95, 1.0, 0.90));
+            draw_circle_lines(tp.x, tp.y, r * 0.98, 2.0, Color::new(0.20, 0.60, 1.0, 0.65));
+        }
+
+        draw_rectangle_lines(0.0, 0.0, layout.world_w_px, layout.world_h_px, 2.0, LINE);
+    }
+
+    fn update_trail(&mut self, sp: Space, tracked: Option<TrackTarget>, info: Option<&TrackedInfo>) {
+        if tracked != self.trail_of {
+            self.trail.clear();
+            self.trail_of = tracked;
+        }
+        let Some(t) = info else {
+            return;
+        };
+        // a jump this long is a rewind or a new run, not a move
+        if self.trail.back().is_some_and(|&b| sp.dist(b, t.p) > 16.0) {
+            self.trail.clear();
+        }
+        if self.trail.back() != Some(&t.p) {
+            self.trail.push_back(t.p);
+            if self.trail.len() > TRAIL_LEN {
+                self.trail.pop_front();
+            }
+        }
+    }
+
+    // trail, sensing radii and what it's steering at, around one on-screen
+    // copy of the tracked agent at tp. f is its interpolated world position
+    fn draw_tracked_extras(&self, sp: Space, cell: f32, tp: Vec2, f: WPos, t: &TrackedInfo) {
+        // walk the trail back from where the agent is drawn, one wrap-aware
+        // step at a time so it doesn't streak across the world at an edge
+        let n = self.trail.len() as f32;
+        let (mut at, mut prev, mut off) = (f, tp, WPos::ZERO);
+        for (k, &q) in self.trail.iter().rev().enumerate() {
+            off += sp.delta(at, q);
+            at = q;
+            let next = tp + px(off) * cell;
+            let fade = 1.0 - k as f32 / n;
+            draw_line(prev.x, prev.y, next.x, next.y, 2.0, Color::new(0.95, 0.95, 1.0, 0.55 * fade));
+            prev = next;
+        }
+
+        if let Some(r) = t.flee_r {
+            draw_circle_lines(tp.x, tp.y, r * cell, 1.0, Color::new(C_WARN.r, C_WARN.g, C_WARN.b, 0.45));
+        }
+        if let Some(r) = t.chase_r {
+            draw_circle_lines(tp.x, tp.y, r * cell, 1.0, Color::new(C_BAD.r, C_BAD.g, C_BAD.b, 0.45));
+        }
+
+        // lines to what it's chasing and from what it's fleeing
+        if let Some(q) = t.prey_at {
+            let e = tp + px(sp.delta(f, q)) * cell;
+            draw_line(tp.x, tp.y, e.x, e.y, 2.0, C_BAD);
+        }
+        if let Some(q) = t.threat_at {
+            let e = tp + px(sp.delta(f, q)) * cell;
+            draw_line(tp.x, tp.y, e.x, e.y, 2.0, C_WARN);
+        }
+
+        // heading, scaled so a full speed step is a couple of cells
+        let h = px(t.heading) * cell * 2.0;
+        if h.length() > 0.5 {
+            let e = tp + h;
+            draw_line(tp.x, tp.y, e.x, e.y, 2.0, C_OK);
+            draw_circle(e.x, e.y, 2.5, C_OK);
+        }
+    }
+}
+
+fn px(v: WPos) -> Vec2 {
+    vec2(v.x, v.y)
+}
+
+pub fn draw_shape(shape: Shape, x: f32, y: f32, r: f32, col: Color) {
+    match shape {
+        Shape::Circle => draw_circle(x, y, r, col),
+        Shape::Triangle => draw_poly(x, y, 3, r, 0.0, col),
+        Shape::Square => draw_poly(x, y, 4, r, 45.0, col),
+        Shape::Diamond => draw_poly(x, y, 4, r, 0.0, col),
+    }
+}
+
+pub fn ground_color(g: Ground) -> Color {
+    match g {
+        Ground::Plain => GRID_BG,
+        Ground::Fertile => G_FERTILE,
+        Ground::Barren => G_BARREN,
+        Ground::Water => G_WATER,
+        Ground::Rock => G_ROCK,
+    }
+}
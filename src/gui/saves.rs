@@ -0,0 +1,249 @@
+use macroquad::prelude::*;
+
+use ecosim::save::{self, SaveMeta};
+use ecosim::util::{fmt_compact, fmt_utc};
+
+use super::config::*;
+
+pub struct SaveEntry {
+    pub path: String,
+    pub name: String,
+    pub meta: Result<SaveMeta, String>,
+}
+
+pub struct SaveBrowser {
+    pub entries: Vec<SaveEntry>,
+    pub sel: usize,
+    pub name_buf: String,
+    pub can_save: bool,
+    confirm_delete: bool,
+}
+
+pub enum BrowserAction {
+    None,
+    Close,
+    Load(String),
+    Save(String),
+    Delete(String),
+}
+
+impl SaveBrowser {
+    pub fn open(can_save: bool) -> Self {
+        let mut b = Self {
+            entries: Vec::new(),
+            sel: 0,
+            name_buf: String::new(),
+            can_save,
+            confirm_delete: false,
+        };
+        b.refresh();
+        b
+    }
+
+    pub fn refresh(&mut self) {
+        let mut paths = Vec::new();
+        if let Ok(rd) = std::fs::read_dir(SAVE_DIR) {
+            for e in rd.flatten() {
+                let p = e.path();
+                if p.extension().and_then(|x| x.to_str()) == Some("bin")
+                    && let Some(s) = p.to_str()
+                {
+                    paths.push(s.to_string());
+                }
+            }
+        }
+        // the old single save slot still shows up so it can be loaded
+        if std::path::Path::new(SAVE_PATH).exists() {
+            paths.push(SAVE_PATH.to_string());
+        }
+
+        self.entries = paths
+            .into_iter()
+            .map(|path| {
+                let name = std::path::Path::new(&path)
+                    .file_stem()
+                    .and_then(|s| s.to_str())
+                    .unwrap_or("?")
+                    .to_string();
+                let meta = save::read_meta(&path).map_err(|e| e.to_string());
+                SaveEntry { path, name, meta }
+            })
+            .collect();
+
+        // newest first, broken files last
+        self.entries.sort_by_key(|e| std::cmp::Reverse(e.meta.as_ref().map(|m| m.time).unwrap_or(0)));
+
+        self.sel = self.sel.min(self.rows().saturating_sub(1));
+        self.confirm_delete = false;
+    }
+
+    // the "new save" row sits on top when saving is allowed
+    fn rows(&self) -> usize {
+        self.entries.len() + self.can_save as usize
+    }
+
+    fn entry_at(&self, row: usize) -> Option<&SaveEntry> {
+        let i = row.checked_sub(self.can_save as usize)?;
+        self.entries.get(i)
+    }
+}
+
+pub fn quick_save_path() -> String {
+    let mut n = 1u32;
+    loop {
+        let p = format!("{}/save_{:03}.bin", SAVE_DIR, n);
+        if !std::path::Path::new(&p).exists() {
+            return p;
+        }
+        n += 1;
+    }
+}
+
+pub fn named_save_path(name: &str) -> String {
+    format!("{}/{}.bin", SAVE_DIR, name)
+}
+
+pub fn browser_input(b: &mut SaveBrowser) -> BrowserAction {
+    while let Some(ch) = get_char_pressed() {
+        let ok = ch.is_ascii_alphanumeric() || ch == '_' || ch == '-';
+        if b.can_save && ok && b.name_buf.len() < 32 {
+            b.name_buf.push(ch);
+        }
+    }
+    if is_key_pressed(KeyCode::Backspace) {
+        b.name_buf.pop();
+    }
+
+    if is_key_pressed(KeyCode::Escape) {
+        return BrowserAction::Close;
+    }
+
+    let n = b.rows().max(1);
+    if is_key_pressed(KeyCode::Up) {
+        b.sel = (b.sel + n - 1) % n;
+        b.confirm_delete = false;
+    }
+    if is_key_pressed(KeyCode::Down) {
+        b.sel = (b.sel + 1) % n;
+        b.confirm_delete = false;
+    }
+
+    if is_mouse_button_pressed(MouseButton::Left) {
+        let (mx, my) = mouse_position();
+        let (x, top, w) = list_rect();
+        let (first, max_rows) = visible_rows(b);
+        if mx >= x && mx <= x + w && my >= top {
+            let i = ((my - top) / ROW_H).floor() as usize;
+            if i < max_rows && first + i < b.rows() {
+                b.sel = first + i;
+                b.confirm_delete = false;
+            }
+        }
+    }
+
+    if is_key_pressed(KeyCode::Enter) {
+        if b.can_save && b.sel == 0 {
+            let path = if b.name_buf.is_empty() { quick_save_path() } else { named_save_path(&b.name_buf) };
+            return BrowserAction::Save(path);
+        }
+        if let Some(e) = b.entry_at(b.sel)
+            && e.meta.is_ok()
+        {
+            return BrowserAction::Load(e.path.clone());
+        }
+    }
+
+    if is_key_pressed(KeyCode::Delete)
+        && let Some(e) = b.entry_at(b.sel)
+    {
+        if b.confirm_delete {
+            return BrowserAction::Delete(e.path.clone());
+        }
+        b.confirm_delete = true;
+    }
+
+    BrowserAction::None
+}
+
+const ROW_H: f32 = 40.0;
+
+fn list_rect() -> (f32, f32, f32) {
+    let x = PAD * 3.0;
+    let top = 120.0;
+    let w = screen_width() - PAD * 6.0;
+    (x, top, w)
+}
+
+// first row shown and how many fit, the list scrolls to keep sel in view.
+// clicks go through this too so they hit the row that's drawn there
+fn visible_rows(b: &SaveBrowser) -> (usize, usize) {
+    let (_, top, _) = list_rect();
+    let max_rows = ((screen_height() - top - PAD) / ROW_H).floor().max(1.0) as usize;
+    (b.sel.saturating_sub(max_rows - 1), max_rows)
+}
+
+pub fn draw_browser(b: &SaveBrowser) {
+    let sw = screen_width();
+    let sh = screen_height();
+
+    draw_rectangle(0.0, 0.0, sw, sh, Color::new(0.0, 0.0, 0.0, 0.72));
+
+    let (x, top, w) = list_rect();
+    draw_text(if b.can_save { "save / load" } else { "load" }, x, 56.0, 36.0, TXT);
+    draw_text(
+        if b.can_save {
+            "up/down: pick   enter: save/load   type: name new save   del del: remove   esc: close"
+        } else {
+            "up/down: pick   enter: load   del del: remove   esc: close"
+        },
+        x,
+        88.0,
+        18.0,
+        SUB,
+    );
+
+    let (first, max_rows) = visible_rows(b);
+
+    for (vis, row) in (first..b.rows()).take(max_rows).enumerate() {
+        let y = top + vis as f32 * ROW_H;
+
+        if row == b.sel {
+            draw_rectangle(x, y + 2.0, w, ROW_H - 4.0, Color::new(0.12, 0.14, 0.20, 1.0));
+            draw_rectangle_lines(x, y + 2.0, w, ROW_H - 4.0, 2.0, LINE);
+        }
+
+        let ty = y + ROW_H * 0.66;
+
+        if b.can_save && row == 0 {
+            let name = if b.name_buf.is_empty() { "(auto name)".to_string() } else { b.name_buf.clone() };
+            draw_text(&format!("+ new save: {}", name), x + 12.0, ty, 20.0, C_OK);
+            continue;
+        }
+
+        let Some(e) = b.entry_at(row) else { continue };
+        let sel = row == b.sel;
+        draw_text(&e.name, x + 12.0, ty, 20.0, if sel { TXT } else { SUB });
+
+        let info = match &e.meta {
+            Ok(m) => format!(
+                "{}   seed {}   step {}   pop {}",
+                fmt_utc(m.time),
+                m.seed,
+                fmt_compact(m.steps),
+                m.pops.iter().map(|n| n.to_string()).collect::<Vec<_>>().join("/")
+            ),
+            Err(err) => format!("unreadable: {}", err),
+        };
+        let col = if e.meta.is_ok() { SUB } else { C_BAD };
+        draw_text(&info, x + 220.0, ty, 18.0, col);
+
+        if sel && b.confirm_delete {
+            let m = measure_text("del again to remove", None, 18, 1.0);
+            draw_text("del again to remove", x + w - m.width - 12.0, ty, 18.0, C_BAD);
+        }
+    }
+
+    if b.entries.is_empty() && !b.can_save {
+        draw_text("no saves yet", x + 12.0, top + ROW_H * 0.66, 20.0, SUB);
+    }
+}
@@ -0,0 +1,159 @@
+use macroquad::prelude::*;
+
+use ecosim::edit::{Edit, MAX_BRUSH, MAX_SPAWN_E};
+use ecosim::world::World;
+
+use super::config::*;
+use super::render::WorldView;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Tool {
+    Track,
+    Grow,
+    Erase,
+    Spawn,
+    Remove,
+}
+
+const TOOLS: [Tool; 5] = [Tool::Track, Tool::Grow, Tool::Erase, Tool::Spawn, Tool::Remove];
+const KEYS: [KeyCode; 5] = [KeyCode::Key1, KeyCode::Key2, KeyCode::Key3, KeyCode::Key4, KeyCode::Key5];
+
+const BTN_W: f32 = 92.0;
+const BTN_H: f32 = 24.0;
+// plant density added per frame while the brush is held
+const PAINT_RATE: i32 = 24;
+
+// the edit palette over the top left of the world view. 1-5 pick a tool,
+// pressing 4 again cycles the species to spawn, [ / ] size the brush or
+// the spawn energy
+pub struct Tools {
+    pub tool: Tool,
+    pub brush: f32,
+    pub species: usize,
+    // None until changed, then it sticks for that species
+    spawn_e: Option<f32>,
+    stroke: bool,
+}
+
+impl Tools {
+    pub fn new() -> Self {
+        Self { tool: Tool::Track, brush: 3.0, species: 0, spawn_e: None, stroke: false }
+    }
+
+    fn rect(i: usize) -> (f32, f32, f32, f32) {
+        (PAD + i as f32 * (BTN_W + 4.0), PAD, BTN_W, BTN_H)
+    }
+
+    pub fn over_palette(&self) -> bool {
+        let (mx, my) = mouse_position();
+        let (x, y, _, h) = Self::rect(0);
+        let (lx, _, lw, _) = Self::rect(TOOLS.len() - 1);
+        mx >= x && mx <= lx + lw && my >= y && my <= y + h + 22.0
+    }
+
+    fn energy(&self, world: &World) -> f32 {
+        self.spawn_e.unwrap_or_else(|| world.species().get(self.species).map_or(1.0, |s| s.def.init_e))
+    }
+
+    // the edit for this frame, and whether it starts a stroke (worth a line
+    // in the event log, the rest of a paint stroke isn't)
+    pub fn input(&mut self, layout: &Layout, view: &WorldView, world: &World) -> Option<(Edit, bool)> {
+        let ns = world.species().len().max(1);
+        for (i, &k) in KEYS.iter().enumerate() {
+            if is_key_pressed(k) {
+                self.pick(TOOLS[i], ns);
+            }
+        }
+        if is_mouse_button_pressed(MouseButton::Left) && self.over_palette() {
+            let (mx, _) = mouse_position();
+            if let Some(i) = (0..TOOLS.len()).find(|&i| {
+                let (x, _, w, _) = Self::rect(i);
+                mx >= x && mx <= x + w
+            }) {
+                self.pick(TOOLS[i], ns);
+            }
+            return None;
+        }
+
+        let bigger = is_key_pressed(KeyCode::RightBracket);
+        let smaller = is_key_pressed(KeyCode::LeftBracket);
+        if bigger || smaller {
+            let f = if bigger { 1.25 } else { 0.8 };
+            if self.tool == Tool::Spawn {
+                self.spawn_e = Some((self.energy(world) * f).clamp(0.05, MAX_SPAWN_E));
+            } else {
+                self.brush = (self.brush * f).clamp(0.5, MAX_BRUSH);
+            }
+        }
+
+        if !is_mouse_button_down(MouseButton::Left) {
+            self.stroke = false;
+            return None;
+        }
+        let (mx, my) = mouse_position();
+        let at = view.screen_to_world(layout, world.space(), mx, my)?;
+        let first = is_mouse_button_pressed(MouseButton::Left);
+
+        let ed = match self.tool {
+            Tool::Track => return None,
+            Tool::Grow | Tool::Erase => {
+                let amount = if self.tool == Tool::Grow { PAINT_RATE } else { -PAINT_RATE };
+                Edit::Plants { at, r: self.brush, amount }
+            }
+            Tool::Spawn if first => Edit::Spawn { species: self.species.min(ns - 1), at, e: self.energy(world) },
+            Tool::Remove if first => Edit::Remove { at, r: self.brush },
+            _ => return None,
+        };
+        let start = !self.stroke;
+        self.stroke = true;
+        Some((ed, start))
+    }
+
+    fn pick(&mut self, t: Tool, ns: usize) {
+        if t == Tool::Spawn && self.tool == Tool::Spawn {
+            self.species = (self.species + 1) % ns;
+            self.spawn_e = None;
+        }
+        self.tool = t;
+    }
+
+    pub fn draw(&self, layout: &Layout, view: &WorldView, world: &World) {
+        let names = world.species_names();
+        let sp_name = names.get(self.species).map_or("?", |n| n.as_str());
+        for (i, &t) in TOOLS.iter().enumerate() {
+            let (x, y, w, h) = Self::rect(i);
+            let label = match t {
+                Tool::Track => "1 track".to_string(),
+                Tool::Grow => "2 plants+".to_string(),
+                Tool::Erase => "3 plants-".to_string(),
+                Tool::Spawn => format!("4 {}", sp_name),
+                Tool::Remove => "5 remove".to_string(),
+            };
+            let on = t == self.tool;
+            draw_rectangle(x, y, w, h, Color::new(PANEL_BG.r, PANEL_BG.g, PANEL_BG.b, 0.9));
+            draw_rectangle_lines(x, y, w, h, if on { 2.0 } else { 1.0 }, if on { C_WARN } else { LINE });
+            draw_text(&label, x + 6.0, y + 17.0, 16.0, if on { TXT } else { SUB });
+        }
+
+        let hint = match self.tool {
+            Tool::Track => None,
+            Tool::Spawn => Some(format!("energy {:.2}  [ ]   4 again: next species", self.energy(world))),
+            _ => Some(format!("brush {:.1}  [ ]", self.brush)),
+        };
+        if let Some(h) = hint {
+            let (x, y, _, bh) = Self::rect(0);
+            draw_text(&h, x, y + bh + 16.0, 16.0, SUB);
+        }
+
+        // brush outline under the mouse
+        let (mx, my) = mouse_position();
+        if matches!(self.tool, Tool::Grow | Tool::Erase | Tool::Remove)
+            && !self.over_palette()
+            && view.screen_to_world(layout, world.space(), mx, my).is_some()
+        {
+            let r = self.brush * view.cell_px(layout, world.space());
+            let col = if self.tool == Tool::Grow { C_PLANT } else { C_BAD };
+            draw_circle_lines(mx, my, r, 1.5, col);
+        }
+    }
+}
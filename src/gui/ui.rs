@@ -0,0 +1,1048 @@
+use std::collections::VecDeque;
+
+use macroquad::prelude::*;
+
+use ecosim::config::*;
+use ecosim::genome::{TRAIT_COUNT, TRAIT_NAMES};
+use ecosim::rewind::Rewind;
+use ecosim::seasons;
+use ecosim::soil;
+use ecosim::species::PLANTS;
+use ecosim::stats::{StatsHistory, TraitStats};
+use ecosim::terrain::TerrainSource;
+use ecosim::util::{fmt_compact, lerp};
+use ecosim::world::{Boundary, LifeEvent, TrackedInfo, World};
+
+use super::config::*;
+use super::life::LifeStory;
+
+pub struct UiState {
+    pub sel: usize,
+    pub log: VecDeque<String>,
+    pub log_cap: usize,
+    pub last_tag: u8,
+    pub seed_buf: String,
+    pub recording: bool,
+    // which genome trait the traits graph shows
+    pub trait_sel: usize,
+}
+
+impl UiState {
+    pub fn new() -> Self {
+        Self {
+            sel: 0,
+            log: VecDeque::new(),
+            log_cap: 10,
+            last_tag: 0,
+            seed_buf: String::new(),
+            recording: false,
+            trait_sel: 0,
+        }
+    }
+
+    pub fn log_push(&mut self, s: String) {
+        self.log.push_front(s);
+        while self.log.len() > self.log_cap {
+            self.log.pop_back();
+        }
+    }
+}
+
+// per species: init, speed, metabolism. then the global rows
+const SPECIES_ROWS: usize = 3;
+const GLOBAL_ROWS: usize = 12;
+
+fn home_rows(set: &SimSettings) -> usize {
+    set.species.len() * SPECIES_ROWS + GLOBAL_ROWS
+}
+
+pub fn home_input(ui: &mut UiState, set: &mut SimSettings) {
+    let n = home_rows(set);
+    ui.sel = ui.sel.min(n - 1);
+
+    if is_key_pressed(KeyCode::Up) {
+        ui.sel = (ui.sel + n - 1) % n;
+    }
+    if is_key_pressed(KeyCode::Down) {
+        ui.sel = (ui.sel + 1) % n;
+    }
+
+    let dec = is_key_pressed(KeyCode::Left) || is_key_pressed(KeyCode::A);
+    let inc = is_key_pressed(KeyCode::Right) || is_key_pressed(KeyCode::D);
+    if dec {
+        apply_home_adjust(set, ui.sel, -1.0);
+    }
+    if inc {
+        apply_home_adjust(set, ui.sel, 1.0);
+    }
+}
+
+pub fn home_seed_input(ui: &mut UiState) -> Option<u64> {
+    while let Some(ch) = get_char_pressed() {
+        if ch.is_ascii_digit() && ui.seed_buf.len() < 20 {
+            ui.seed_buf.push(ch);
+        }
+    }
+
+    if is_key_pressed(KeyCode::Backspace) {
+        ui.seed_buf.pop();
+    }
+
+    if ui.seed_buf.is_empty() {
+        return None;
+    }
+
+    ui.seed_buf.parse::<u64>().ok()
+}
+
+pub fn home_mouse_input(layout: &Layout, ui: &mut UiState, set: &mut SimSettings) {
+    if !is_mouse_button_pressed(MouseButton::Left) {
+        return;
+    }
+
+    let (mx, my) = mouse_position();
+
+    let x = PAD * 2.0;
+    let y = layout.world_h_px * 0.30;
+    let w = layout.world_w_px - PAD * 4.0;
+    let h = layout.world_h_px * 0.60;
+
+    let rows_top = y + 140.0;
+    let footer_h = 44.0;
+    let rows_h = (h - (rows_top - y) - footer_h).max(0.0);
+
+    let row_h = 34.0;
+    let max_rows = (rows_h / row_h).floor() as usize;
+    let show_rows = home_rows(set).min(max_rows);
+
+    if mx < x || mx > x + w || my < rows_top || my > rows_top + show_rows as f32 * row_h {
+        return;
+    }
+
+    let vis = ((my - rows_top) / row_h).floor() as usize;
+    if vis >= show_rows {
+        return;
+    }
+
+    let i = first_row(ui.sel, show_rows) + vis;
+    ui.sel = i;
+
+    let btn_w = 34.0;
+    let btn_h = row_h - 10.0;
+    let by = rows_top + vis as f32 * row_h + 5.0;
+
+    let minus_x = x + w - 2.0 * btn_w - 18.0;
+    let plus_x = x + w - btn_w - 12.0;
+
+    if in_rect(mx, my, minus_x, by, btn_w, btn_h) {
+        apply_home_adjust(set, i, -1.0);
+    } else if in_rect(mx, my, plus_x, by, btn_w, btn_h) {
+        apply_home_adjust(set, i, 1.0);
+    }
+}
+
+// settings list scrolls so the selected row stays visible
+fn first_row(sel: usize, show_rows: usize) -> usize {
+    sel.saturating_sub(show_rows.saturating_sub(1))
+}
+
+fn in_rect(mx: f32, my: f32, x: f32, y: f32, w: f32, h: f32) -> bool {
+    mx >= x && mx <= x + w && my >= y && my <= y + h
+}
+
+fn apply_home_adjust(set: &mut SimSettings, idx: usize, dir: f32) {
+    let ns = set.species.len() * SPECIES_ROWS;
+    if idx < ns {
+        let d = &mut set.species[idx / SPECIES_ROWS];
+        match idx % SPECIES_ROWS {
+            0 => {
+                let step = (d.init as f32 * 0.05).round().max(1.0);
+                d.init = (d.init as f32 + step * dir).clamp(0.0, 20000.0) as u32;
+            }
+            1 => d.speed = (d.speed + 0.02 * dir).clamp(0.04, 0.85),
+            _ => d.met = (d.met + 0.002 * dir).clamp(0.001, 0.080),
+        }
+        return;
+    }
+
+    match idx - ns {
+        0 => set.plant_grow = ((set.plant_grow as i32) + (1.0 * dir) as i32).clamp(0, 12) as u8,
+        1 => set.plant_spread = (set.plant_spread + 0.03 * dir).clamp(0.0, 0.95),
+        2 => set.eat_radius = (set.eat_radius + 0.05 * dir).clamp(0.15, 2.00),
+        3 => set.world_w = (set.world_w + (20.0 * dir) as i32).clamp(MIN_DIM, MAX_DIM),
+        4 => set.world_h = (set.world_h + (20.0 * dir) as i32).clamp(MIN_DIM, MAX_DIM),
+        5 => set.mutation = (set.mutation + 0.01 * dir).clamp(0.0, 0.50),
+        6 => set.threads = ((set.threads as i32) + dir as i32).clamp(0, 64) as u32,
+        // flips between flat and random. an image steps to random and is
+        // gone, only a settings file or --terrain brings one back
+        7 => set.terrain = if set.terrain == TerrainSource::Flat { TerrainSource::Random } else { TerrainSource::Flat },
+        8 => {
+            let all = Boundary::ALL;
+            let i = all.iter().position(|&b| b == set.boundary).unwrap_or(0) as i32;
+            set.boundary = all[(i + dir as i32).rem_euclid(all.len() as i32) as usize];
+        }
+        9 => set.season_len = (set.season_len as i32 + (500.0 * dir) as i32).clamp(0, 50_000) as u32,
+        10 => set.day_len = (set.day_len as i32 + (50.0 * dir) as i32).clamp(0, 5_000) as u32,
+        11 => set.soil = !set.soil,
+        _ => {}
+    }
+}
+
+fn home_row_labels(set: &SimSettings) -> Vec<String> {
+    let mut rows = Vec::new();
+    for d in &set.species {
+        rows.push(format!("{}: start count: {}", d.name, d.init));
+        rows.push(format!("{}: speed: {:.2}", d.name, d.speed));
+        rows.push(format!("{}: metabolism: {:.3}", d.name, d.met));
+    }
+    rows.push(format!("plant grow: {}", set.plant_grow));
+    rows.push(format!("plant spread: {:.2}", set.plant_spread));
+    rows.push(format!("eat radius: {:.2}", set.eat_radius));
+    rows.push(format!("world width: {}", set.world_w));
+    rows.push(format!("world height: {}", set.world_h));
+    rows.push(format!("mutation: {:.2}", set.mutation));
+    rows.push(if set.threads == 0 { "threads: auto".to_string() } else { format!("threads: {}", set.threads) });
+    rows.push(format!("terrain: {}", set.terrain.name()));
+    rows.push(format!("edges: {}", set.boundary.name()));
+    rows.push(if set.season_len == 0 { "year: no seasons".to_string() } else { format!("year: {} steps", set.season_len) });
+    rows.push(if set.day_len == 0 { "day: always light".to_string() } else { format!("day: {} steps", set.day_len) });
+    rows.push(format!("soil: {}", if set.soil { "on" } else { "off" }));
+    rows
+}
+
+pub fn draw_home(layout: &Layout, ui: &UiState, set: &SimSettings, cpu_threads: usize, seed: u64) {
+    let cx = layout.world_w_px * 0.5;
+
+    draw_text_center("ecosim", cx, layout.world_h_px * 0.13, 64.0, TXT);
+
+    draw_text_center(
+        "tiny ecosystem sim: plants regrow/spread, herbs eat plants, preds hunt herbs",
+        cx,
+        layout.world_h_px * 0.18,
+        20.0,
+        SUB,
+    );
+
+    draw_text_center("enter: start   l: load a save   esc: quit", cx, layout.world_h_px * 0.22, 20.0, SUB);
+    draw_text_center(
+        "up/down + left/right (or a/d): edit settings   o / w: load / save settings file",
+        cx,
+        layout.world_h_px * 0.25,
+        20.0,
+        SUB,
+    );
+
+    let seed_line = if ui.seed_buf.is_empty() {
+        format!("seed: {}  (type digits to override)", seed)
+    } else {
+        format!("seed: {}  (typing: {})", seed, ui.seed_buf)
+    };
+    draw_text_center(&seed_line, cx, layout.world_h_px * 0.28, 18.0, SUB);
+
+    let (pl, pc) = perf_label(set, cpu_threads);
+
+    let x = PAD * 2.0;
+    let y = layout.world_h_px * 0.30;
+    let w = layout.world_w_px - PAD * 4.0;
+    let h = layout.world_h_px * 0.60;
+
+    draw_rectangle(x, y, w, h, Color::new(0.06, 0.07, 0.10, 1.0));
+    draw_rectangle_lines(x, y, w, h, 2.0, LINE);
+
+    draw_text("intro", x + 18.0, y + 26.0, 20.0, SUB);
+    draw_text(
+        "click an agent to track it (shows in panel + gets highlighted)",
+        x + 18.0,
+        y + 52.0,
+        18.0,
+        SUB,
+    );
+    draw_text(
+        "s: save   l: saves   space: pause   r: restart   n: new seed   +/-: speed   c: csv   v: replay   t: trait",
+        x + 18.0,
+        y + 74.0,
+        18.0,
+        SUB,
+    );
+
+    draw_text("settings", x + 18.0, y + 110.0, 20.0, SUB);
+
+    let rows = home_row_labels(set);
+
+    let rows_top = y + 140.0;
+    let footer_h = 44.0;
+    let rows_h = (h - (rows_top - y) - footer_h).max(0.0);
+
+    let row_h = 34.0;
+    let text_sz = 20.0;
+    let text_base = rows_top + row_h * 0.72;
+
+    let max_rows = (rows_h / row_h).floor() as usize;
+    let show_rows = rows.len().min(max_rows);
+
+    let bx = x + 14.0;
+    let bw = w - 28.0;
+
+    let (mx, my) = mouse_position();
+
+    let first = first_row(ui.sel, show_rows);
+
+    for (vis, (i, label)) in rows.iter().enumerate().skip(first).take(show_rows).enumerate() {
+        let ry = rows_top + vis as f32 * row_h;
+
+        if i == ui.sel {
+            draw_rectangle(bx, ry + 3.0, bw, row_h - 6.0, Color::new(0.12, 0.14, 0.20, 1.0));
+            draw_rectangle_lines(bx, ry + 3.0, bw, row_h - 6.0, 2.0, LINE);
+        }
+
+        let btn_w = 34.0;
+        let btn_h = row_h - 10.0;
+        let by = ry + 5.0;
+
+        let minus_x = x + w - 2.0 * btn_w - 18.0;
+        let plus_x = x + w - btn_w - 12.0;
+
+        let hover_minus = in_rect(mx, my, minus_x, by, btn_w, btn_h);
+        let hover_plus = in_rect(mx, my, plus_x, by, btn_w, btn_h);
+
+        let base_btn = Color::new(0.10, 0.11, 0.16, 1.0);
+        let hot_btn = Color::new(0.14, 0.16, 0.24, 1.0);
+
+        draw_rectangle(minus_x, by, btn_w, btn_h, if hover_minus { hot_btn } else { base_btn });
+        draw_rectangle_lines(
+            minus_x,
+            by,
+            btn_w,
+            btn_h,
+            2.0,
+            if hover_minus { Color::new(0.30, 0.55, 1.0, 0.90) } else { LINE },
+        );
+
+        draw_rectangle(plus_x, by, btn_w, btn_h, if hover_plus { hot_btn } else { base_btn });
+        draw_rectangle_lines(
+            plus_x,
+            by,
+            btn_w,
+            btn_h,
+            2.0,
+            if hover_plus { Color::new(0.30, 0.55, 1.0, 0.90) } else { LINE },
+        );
+
+        let m1 = measure_text("-", None, 20, 1.0);
+        draw_text(
+            "-",
+            minus_x + btn_w * 0.5 - m1.width * 0.5,
+            by + btn_h * 0.72,
+            20.0,
+            if hover_minus { TXT } else { SUB },
+        );
+
+        let m2 = measure_text("+", None, 20, 1.0);
+        draw_text(
+            "+",
+            plus_x + btn_w * 0.5 - m2.width * 0.5,
+            by + btn_h * 0.72,
+            20.0,
+            if hover_plus { TXT } else { SUB },
+        );
+
+        draw_text(
+            label,
+            x + 22.0,
+            text_base + vis as f32 * row_h,
+            text_sz,
+            if i == ui.sel { TXT } else { SUB },
+        );
+    }
+
+    let perf_y = y + h - 16.0;
+    draw_text(&format!("perf: {}", pl), x + 18.0, perf_y, 20.0, pc);
+
+    draw_rectangle(layout.panel_x, 0.0, layout.panel_w, layout.world_h_px, PANEL_BG);
+    draw_rectangle_lines(layout.panel_x, 0.0, layout.panel_w, layout.world_h_px, 2.0, LINE);
+
+    let px = layout.panel_x + PAD;
+    let mut py = PAD + 16.0;
+
+    draw_text("controls", px, py, 20.0, SUB);
+    py += 26.0;
+    draw_text("click: track agent", px, py, 18.0, SUB);
+    py += 20.0;
+    draw_text("space: pause/resume", px, py, 18.0, SUB);
+    py += 20.0;
+    draw_text("r: restart   n: new seed", px, py, 18.0, SUB);
+    py += 20.0;
+    draw_text("s: quick save   l: save browser", px, py, 18.0, SUB);
+    py += 20.0;
+    draw_text("c: record csv   v: write replay", px, py, 18.0, SUB);
+    py += 20.0;
+    draw_text("+/-: speed", px, py, 18.0, SUB);
+    py += 30.0;
+
+    draw_text("legend", px, py, 20.0, SUB);
+    py += 26.0;
+    draw_text("plants", px, py, 20.0, C_PLANT);
+    py += 24.0;
+    for d in &set.species {
+        draw_text(&d.name, px, py, 20.0, species_color(d));
+        py += 24.0;
+    }
+    py += 6.0;
+
+    draw_text("panel graphs", px, py, 18.0, SUB);
+    py += 22.0;
+    draw_text("population / flows / plants / energy", px, py, 18.0, SUB);
+}
+
+pub fn draw_pause_overlay(layout: &Layout) {
+    let w = layout.world_w_px;
+    let h = layout.world_h_px;
+    draw_rectangle(0.0, 0.0, w, h, Color::new(0.0, 0.0, 0.0, 0.30));
+    draw_text_center("paused", w * 0.5, h * 0.18, 48.0, TXT);
+    draw_text_center(
+        "space: resume   r: restart   n: new seed   l: saves   enter: home",
+        w * 0.5,
+        h * 0.24,
+        20.0,
+        SUB,
+    );
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn draw_panel(
+    layout: &Layout,
+    world: &World,
+    hist: &StatsHistory,
+    rewind: &Rewind,
+    ui: &UiState,
+    mode: SimMode,
+    steps: u64,
+    seed: u64,
+    speed: f32,
+    set: &SimSettings,
+    tracked: Option<TrackedInfo>,
+    life: &LifeStory,
+) {
+    let x = layout.panel_x;
+    let w = layout.panel_w;
+    let h = layout.world_h_px;
+
+    let gx = x + PAD;
+    let gw = w - PAD * 2.0;
+
+    let gap = 10.0;
+    let gh = ((h - 300.0 - gap * 4.0) / 5.0).clamp(64.0, 98.0);
+
+    let g5y = h - PAD - SCRUB_H - gh;
+    let g4y = g5y - gap - gh;
+    let g3y = g4y - gap - gh;
+    let g2y = g3y - gap - gh;
+    let g1y = g2y - gap - gh;
+
+    draw_rectangle(x, 0.0, w, h, PANEL_BG);
+    draw_rectangle_lines(x, 0.0, w, h, 2.0, LINE);
+
+    let mut cy = PAD + 10.0;
+
+    draw_text("stats", x + PAD, cy, 30.0, TXT);
+    cy += 34.0;
+
+    let c = world.counts();
+    row(x + PAD, &mut cy, "step", &fmt_compact(steps), TXT);
+    row(x + PAD, &mut cy, "seed", &format!("{}", seed), SUB);
+    row(
+        x + PAD,
+        &mut cy,
+        "mode",
+        match mode {
+            SimMode::Home => "home",
+            SimMode::Running => "run",
+            SimMode::Paused => "pause",
+        },
+        SUB,
+    );
+    row(x + PAD, &mut cy, "speed", &format!("{:.2}x", speed), SUB);
+    if let Some(si) = seasons::season(set, world.now()) {
+        let v = format!("{}  growth x{:.2}", seasons::season_name(set, si), seasons::growth(set, world.now()));
+        row(x + PAD, &mut cy, "season", &v, season_tint(set, si));
+    }
+    if set.day_len > 0 {
+        let l = world.light();
+        let v = format!("{}  light {:.0}%", if seasons::is_night(l) { "night" } else { "day" }, l * 100.0);
+        row(x + PAD, &mut cy, "time", &v, SUB);
+    }
+    if ui.recording {
+        row(x + PAD, &mut cy, "csv", "recording", C_BAD);
+    }
+
+    cy += 10.0;
+    row_color(x + PAD, &mut cy, "plants avg", &format!("{:.3}", c.plants_avg), C_PLANT);
+    if set.soil {
+        row_color(x + PAD, &mut cy, "soil avg", &format!("{:.3}", soil::average(world.soil())), C_SOIL);
+    }
+    for (spc, p) in world.species().iter().zip(&c.pops) {
+        row_color(x + PAD, &mut cy, &spc.def.name, &format!("{}", p.n), species_color(&spc.def));
+    }
+
+    cy += 12.0;
+    draw_text("tracked", x + PAD, cy, 20.0, SUB);
+    cy += 26.0;
+
+    if let Some(t) = &tracked {
+        let k = world.species().get(t.species).map(|s| s.def.name.as_str()).unwrap_or("?");
+        let family = if t.parent != 0 { format!("   child of #{}, born {}", t.parent, t.born) } else { format!("   born {}", t.born) };
+        draw_text(&format!("{} #{}{}", k, t.id, family), x + PAD, cy, 18.0, TXT);
+        cy += 20.0;
+        draw_text(&format!("energy {:.3}   age {}", t.e, t.age), x + PAD, cy, 18.0, SUB);
+        cy += 20.0;
+        let radius = |r: Option<f32>| r.map_or("-".to_string(), |r| format!("{:.1}", r));
+        draw_text(
+            &format!(
+                "pos {:.1}, {:.1} ({}{})   flee {}  chase {}",
+                t.p.x,
+                t.p.y,
+                world.ground()[world.space().cell_of(t.p)].name(),
+                if set.soil { format!(", soil {}", world.soil()[world.space().cell_of(t.p)]) } else { String::new() },
+                radius(t.flee_r),
+                radius(t.chase_r)
+            ),
+            x + PAD,
+            cy,
+            18.0,
+            SUB,
+        );
+        cy += 20.0;
+        let g = t.g;
+        draw_text(
+            &format!("spd {:.2}  sns {:.2}  rep {:.2}  drv {:.2}", g.speed, g.sense, g.repro, g.drive),
+            x + PAD,
+            cy,
+            18.0,
+            SUB,
+        );
+        cy += 8.0;
+        let col = world.species().get(t.species).map_or(TXT, |s| species_color(&s.def));
+        draw_sparkline(gx, cy, gw, SPARK_H, &life.energy, col);
+        cy += SPARK_H + 4.0;
+    } else if let (Some(d), Some(tt)) = (&life.death, life.target) {
+        // the tracked agent died, say how instead of forgetting it
+        let name = |s: usize| world.species().get(s).map_or("?", |s| s.def.name.as_str());
+        draw_text(&format!("{} #{} died at step {}", name(tt.species), tt.id, d.step), x + PAD, cy, 18.0, TXT);
+        cy += 20.0;
+        let cause = match d.cause {
+            LifeEvent::Eaten(by) => format!("eaten by {}", name(by)),
+            LifeEvent::Removed => "removed by hand".to_string(),
+            _ => "starved".to_string(),
+        };
+        draw_text(&format!("{} at age {}", cause, d.age), x + PAD, cy, 18.0, C_BAD);
+        cy += 20.0;
+        draw_text(&format!("{} offspring   {} meals", life.kids, life.meals), x + PAD, cy, 18.0, SUB);
+        cy += 8.0;
+        draw_sparkline(gx, cy, gw, SPARK_H, &life.energy, SUB);
+        cy += SPARK_H + 4.0;
+    } else {
+        draw_text("click an agent to track", x + PAD, cy, 18.0, SUB);
+        cy += 12.0;
+    }
+
+    // while something is tracked its own story replaces the world's log
+    let lines: Vec<String> = match life.target {
+        Some(tt) if tracked.is_some() || life.death.is_some() => {
+            cy += 8.0;
+            draw_text(&format!("life of #{}   {} kids  {} meals", tt.id, life.kids, life.meals), x + PAD, cy, 20.0, SUB);
+            life.log.iter().map(|e| life_line(world, e.step, e.ev, e.n)).collect()
+        }
+        _ => {
+            cy += 8.0;
+            draw_text("event log", x + PAD, cy, 20.0, SUB);
+            ui.log.iter().cloned().collect()
+        }
+    };
+    cy += 24.0;
+
+    let mut ly = cy;
+    for s in &lines {
+        if ly > g1y - 10.0 {
+            break;
+        }
+        draw_text(s, x + PAD, ly, 18.0, SUB);
+        ly += 20.0;
+    }
+
+    let cols: Vec<Color> = world.species().iter().map(|s| species_color(&s.def)).collect();
+    let names = world.species_names();
+    let legend: Vec<(&str, Color)> = names.iter().map(|n| n.as_str()).zip(cols.iter().copied()).collect();
+
+    // +x / -x per species, x being the first letter of its name
+    let flow_names: Vec<(String, String)> = names
+        .iter()
+        .map(|n| {
+            let c = n.chars().next().unwrap_or('?');
+            (format!("+{}", c), format!("-{}", c))
+        })
+        .collect();
+    let mut flow_legend: Vec<(&str, Color)> = Vec::new();
+    for ((b, d), &col) in flow_names.iter().zip(&cols) {
+        flow_legend.push((b, col));
+        flow_legend.push((d, col));
+    }
+
+    let bands = cycle_bands(hist, world, set, steps);
+
+    draw_graph_block(gx, g1y, gw, gh, "population", &legend, &bands);
+    draw_graph_pop(gx, g1y, gw, gh, hist, &cols);
+
+    draw_graph_block(gx, g2y, gw, gh, "flows per tick", &flow_legend, &bands);
+    draw_graph_flows(gx, g2y, gw, gh, hist, &cols);
+
+    draw_graph_block(gx, g3y, gw, gh, "plants avg", &[("avg", C_PLANT)], &bands);
+    draw_graph_plants(gx, g3y, gw, gh, hist);
+
+    draw_graph_block(gx, g4y, gw, gh, "avg energy", &legend, &bands);
+    draw_graph_energy(gx, g4y, gw, gh, hist, &cols);
+
+    let title = format!("trait: {} (t)", TRAIT_NAMES[ui.trait_sel % TRAIT_COUNT]);
+    draw_graph_block(gx, g5y, gw, gh, &title, &legend, &bands);
+    draw_graph_traits(gx, g5y, gw, gh, hist, &cols, ui.trait_sel % TRAIT_COUNT);
+
+    draw_scrubber(layout, rewind, steps);
+}
+
+// a stretch of graph history in one season and one time of day, as
+// fractions of the graph width
+struct Band {
+    t0: f32,
+    t1: f32,
+    tint: Option<Color>,
+    night: bool,
+}
+
+fn cycle_bands(hist: &StatsHistory, world: &World, set: &SimSettings, steps: u64) -> Vec<Band> {
+    let n = hist.len();
+    if n < 2 || (set.season_len == 0 && set.day_len == 0) {
+        return Vec::new();
+    }
+    // history goes by run steps, the cycles by world steps
+    let off = world.now() as i64 - steps as i64;
+    let key = |i: usize| {
+        let s = (hist.steps[i] as i64 + off).max(0) as u64;
+        (seasons::season(set, s), seasons::is_night(seasons::light(set, s)))
+    };
+
+    let mut out = Vec::new();
+    let mut start = 0;
+    for i in 1..n {
+        if key(i) == key(start) && i < n - 1 {
+            continue;
+        }
+        let (season, night) = key(start);
+        let t = |k: usize| k as f32 / (n - 1) as f32;
+        out.push(Band { t0: t(start), t1: t(i), tint: season.map(|si| season_tint(set, si)), night });
+        start = i;
+    }
+    out
+}
+
+// fixed colours for the four seasons, green for good growth through to
+// blue for poor growth in any other table
+fn season_tint(set: &SimSettings, si: usize) -> Color {
+    match set.season_grow.len() {
+        0 | 4 => [C_OK, C_WARN, Color::new(0.90, 0.50, 0.20, 1.0), Color::new(0.45, 0.65, 1.0, 1.0)][si % 4],
+        _ => {
+            let t = (set.season_grow[si] / 2.0).clamp(0.0, 1.0);
+            Color::new(lerp(0.45, C_OK.r, t), lerp(0.65, C_OK.g, t), lerp(1.0, C_OK.b, t), 1.0)
+        }
+    }
+}
+
+fn scrub_rect(layout: &Layout) -> (f32, f32, f32, f32) {
+    let x = layout.panel_x + PAD;
+    let w = layout.panel_w - PAD * 2.0;
+    (x, layout.world_h_px - PAD - SCRUB_H + 8.0, w, SCRUB_H - 8.0)
+}
+
+// x of a step on the strip, which runs from the oldest snapshot to now
+fn scrub_x(rewind: &Rewind, steps: u64, x: f32, w: f32, at: u64) -> f32 {
+    let s0 = rewind.snaps().front().map_or(0, |s| s.steps);
+    let s1 = steps.max(s0 + 1);
+    x + (at.saturating_sub(s0)) as f32 / (s1 - s0) as f32 * w
+}
+
+// snapshot under the mouse, the nearest one within a few px
+fn scrub_hover(layout: &Layout, rewind: &Rewind, steps: u64) -> Option<usize> {
+    let (x, y, w, h) = scrub_rect(layout);
+    let (mx, my) = mouse_position();
+    if !in_rect(mx, my, x - 6.0, y, w + 12.0, h) {
+        return None;
+    }
+    rewind
+        .snaps()
+        .iter()
+        .enumerate()
+        .map(|(i, s)| (i, (scrub_x(rewind, steps, x, w, s.steps) - mx).abs()))
+        .filter(|&(_, d)| d <= 6.0)
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(i, _)| i)
+}
+
+// click a snapshot to go back to it, shift-click to fork from it with a
+// new seed. gives the snapshot index and whether to fork
+pub fn scrubber_input(layout: &Layout, rewind: &Rewind, steps: u64) -> Option<(usize, bool)> {
+    if !is_mouse_button_pressed(MouseButton::Left) {
+        return None;
+    }
+    let i = scrub_hover(layout, rewind, steps)?;
+    let fork = is_key_down(KeyCode::LeftShift) || is_key_down(KeyCode::RightShift);
+    Some((i, fork))
+}
+
+fn draw_scrubber(layout: &Layout, rewind: &Rewind, steps: u64) {
+    let (x, y, w, h) = scrub_rect(layout);
+    draw_rectangle(x, y, w, h, GRID_BG);
+    draw_rectangle_lines(x, y, w, h, 1.0, LINE);
+
+    let hover = scrub_hover(layout, rewind, steps);
+    for (i, s) in rewind.snaps().iter().enumerate() {
+        let sx = scrub_x(rewind, steps, x, w, s.steps);
+        let on = hover == Some(i);
+        draw_line(sx, y + 3.0, sx, y + h - 3.0, if on { 2.0 } else { 1.0 }, if on { TXT } else { SUB });
+    }
+    let nx = scrub_x(rewind, steps, x, w, steps);
+    draw_line(nx, y, nx, y + h, 2.0, C_WARN);
+
+    let label = match hover {
+        Some(i) => format!("step {}  click: go back  shift: fork", rewind.snaps()[i].steps),
+        None => format!("rewind: {} snapshots", rewind.snaps().len()),
+    };
+    draw_text(&label, x, y - 3.0, 16.0, SUB);
+}
+
+const SPARK_H: f32 = 28.0;
+// more than this and the tree stops being readable anyway
+const TREE_MAX: usize = 600;
+
+// descendant tree of one agent over the bottom left of the world view.
+// time runs left to right from its birth to now, one row per agent in
+// depth first order, each line spanning its life
+pub fn draw_family_tree(layout: &Layout, world: &World, root: u32) {
+    let w = (layout.world_w_px * 0.5).clamp(260.0, 560.0);
+    let h = (layout.world_h_px * 0.4).clamp(160.0, 340.0);
+    let (x, y) = (PAD, layout.world_h_px - PAD - h);
+    draw_rectangle(x, y, w, h, Color::new(PANEL_BG.r, PANEL_BG.g, PANEL_BG.b, 0.92));
+    draw_rectangle_lines(x, y, w, h, 2.0, LINE);
+
+    let nodes = world.lineage().tree(root);
+    let Some(top) = nodes.first() else {
+        draw_text(&format!("no family records for #{} (k: close)", root), x + 10.0, y + 20.0, 18.0, SUB);
+        return;
+    };
+
+    let alive = nodes.iter().skip(1).filter(|n| n.died.is_none()).count();
+    let gens = nodes.iter().map(|n| n.depth).max().unwrap_or(0);
+    let head = format!("family of #{}: {} descendants, {} alive, {} gen (k)", root, nodes.len() - 1, alive, gens);
+    draw_text(&head, x + 10.0, y + 20.0, 18.0, SUB);
+
+    let (px, py, pw, ph) = (x + 10.0, y + 32.0, w - 20.0, h - 42.0);
+    let shown = nodes.len().min(TREE_MAX);
+    if shown < nodes.len() {
+        draw_text(&format!("first {} shown", TREE_MAX), x + w - 120.0, y + 20.0, 16.0, SUB);
+    }
+
+    let (t0, t1) = (top.born, world.now().max(top.born + 1));
+    let tx = |t: u64| px + (t.saturating_sub(t0)) as f32 / (t1 - t0) as f32 * pw;
+    let row = ph / shown as f32;
+    let ry = |i: usize| py + (i as f32 + 0.5) * row;
+    let thick = row.clamp(1.0, 3.0);
+
+    for (i, n) in nodes.iter().take(shown).enumerate() {
+        let (x0, x1) = (tx(n.born), tx(n.died.unwrap_or(t1)));
+        let col = match n.died {
+            None => world.species().get(n.species).map_or(TXT, |s| species_color(&s.def)),
+            Some(_) => Color::new(SUB.r, SUB.g, SUB.b, 0.45),
+        };
+        if let Some(up) = n.up {
+            draw_line(x0, ry(up), x0, ry(i), 1.0, Color::new(SUB.r, SUB.g, SUB.b, 0.35));
+        }
+        draw_line(x0, ry(i), x1.max(x0 + 1.0), ry(i), thick, col);
+    }
+    draw_circle(tx(top.born), ry(0), 3.0, TXT);
+}
+
+fn life_line(world: &World, step: u64, ev: LifeEvent, n: u32) -> String {
+    let name = |s: usize| world.species().get(s).map_or("?", |s| s.def.name.as_str());
+    let what = match ev {
+        LifeEvent::Ate(q) => format!("ate a {}", name(q)),
+        LifeEvent::Grazed(e) => format!("grazed +{:.2} over {} steps", e, n),
+        LifeEvent::Bred(id) => format!("had kid #{}", id),
+        LifeEvent::NearMiss(q) => format!("near miss, {}", name(q)),
+        LifeEvent::Starved => "starved".to_string(),
+        LifeEvent::Eaten(q) => format!("eaten by {}", name(q)),
+        LifeEvent::Removed => "removed by hand".to_string(),
+    };
+    format!("{:>6}  {}", step, what)
+}
+
+// energy over the tracked stretch of a life, scaled to its own range
+fn draw_sparkline(x: f32, y: f32, w: f32, h: f32, data: &VecDeque<f32>, col: Color) {
+    draw_rectangle(x, y, w, h, GRID_BG);
+    let lo = data.iter().copied().fold(f32::INFINITY, f32::min).min(0.0);
+    let hi = data.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+    if data.len() >= 2 {
+        draw_series_f(x, y, w, h, data, |v| map_clamped(v, lo, hi, y + h - 2.0, y + 2.0), col);
+    }
+    draw_rectangle_lines(x, y, w, h, 1.0, LINE);
+}
+
+fn perf_label(set: &SimSettings, cpu_threads: usize) -> (&'static str, Color) {
+    // neighbour lookups go through the bucket grid, so cost is roughly
+    // linear in agents; hunters search wider than grazers
+    let mut agent_cost = 0.0;
+    for d in &set.species {
+        let hunts = d.eats.iter().any(|e| e != PLANTS);
+        agent_cost += d.init as f32 * if hunts { 80.0 } else { 40.0 };
+    }
+    let base = (set.world_w * set.world_h) as f32 * 8.0;
+    let est = agent_cost + base;
+
+    let t = if set.threads == 0 { cpu_threads } else { (set.threads as usize).min(cpu_threads) };
+    let t = t.max(1) as f32;
+    let smooth = 220000.0 * t;
+    let ok = 520000.0 * t;
+
+    if est <= smooth {
+        ("smooth", C_OK)
+    } else if est <= ok {
+        ("ok", C_WARN)
+    } else {
+        ("heavy", C_BAD)
+    }
+}
+
+fn draw_graph_block(x: f32, y: f32, w: f32, h: f32, title: &str, legend: &[(&str, Color)], bands: &[Band]) {
+    draw_rectangle(x, y, w, h, Color::new(0.06, 0.07, 0.10, 1.0));
+    let (px, py, pw, ph) = graph_plot_rect(x, y, w, h);
+    for b in bands {
+        let (bx, bw) = (px + b.t0 * pw, (b.t1 - b.t0) * pw);
+        if let Some(c) = b.tint {
+            draw_rectangle(bx, py, bw, ph, Color::new(c.r, c.g, c.b, 0.08));
+        }
+        if b.night {
+            draw_rectangle(bx, py, bw, ph, Color::new(0.0, 0.0, 0.0, 0.30));
+        }
+    }
+    draw_rectangle_lines(x, y, w, h, 2.0, LINE);
+
+    draw_text(title, x + 10.0, y + 20.0, 18.0, SUB);
+
+    let mut lx = x + w - 10.0;
+    for (name, col) in legend.iter().rev() {
+        let m = measure_text(name, None, 16, 1.0);
+        lx -= m.width;
+        draw_text(name, lx, y + 20.0, 16.0, *col);
+        lx -= 10.0;
+    }
+}
+
+fn graph_plot_rect(x: f32, y: f32, w: f32, h: f32) -> (f32, f32, f32, f32) {
+    let head = 32.0;
+    (x, y + head, w, (h - head).max(0.0))
+}
+
+fn draw_y_ticks(x: f32, y: f32, w: f32, h: f32, vmin: f32, vmax: f32, fmt: fn(f32) -> String) {
+    let tx = x + 6.0;
+    for k in 0..=4 {
+        let t = k as f32 / 4.0;
+        let yy = y + h - t * h;
+        draw_line(x, yy, x + w, yy, 1.0, Color::new(LINE.r, LINE.g, LINE.b, 0.55));
+        draw_line(x, yy, x + 6.0, yy, 2.0, LINE);
+        let v = vmin + (vmax - vmin) * t;
+        draw_text(&fmt(v), tx, yy - 2.0, 14.0, SUB);
+    }
+}
+
+fn fmt_int(v: f32) -> String {
+    format!("{}", v.round() as i32)
+}
+
+fn fmt_small(v: f32) -> String {
+    format!("{:.2}", v)
+}
+
+fn draw_graph_pop(x: f32, y: f32, w: f32, h: f32, hist: &StatsHistory, cols: &[Color]) {
+    if hist.len() < 2 {
+        return;
+    }
+    let vmax = (hist.max_agents_recent().max(50) as f32) * 1.10;
+    let (px, py, pw, ph) = graph_plot_rect(x, y, w, h);
+    draw_y_ticks(px, py, pw, ph, 0.0, vmax, fmt_int);
+
+    for (pop, &col) in hist.pops.iter().zip(cols) {
+        draw_series_u(px, py, pw, ph, &pop.n, |v| map_clamped(v as f32, 0.0, vmax, py + ph, py), col);
+    }
+}
+
+fn draw_graph_flows(x: f32, y: f32, w: f32, h: f32, hist: &StatsHistory, cols: &[Color]) {
+    if hist.len() < 2 {
+        return;
+    }
+    let vmax = (hist.max_flow_recent().max(5) as f32) * 1.20;
+    let (px, py, pw, ph) = graph_plot_rect(x, y, w, h);
+    draw_y_ticks(px, py, pw, ph, 0.0, vmax, fmt_int);
+
+    for (pop, &col) in hist.pops.iter().zip(cols) {
+        draw_series_u(px, py, pw, ph, &pop.births, |v| map_clamped(v as f32, 0.0, vmax, py + ph, py), col);
+        draw_series_u_dim(px, py, pw, ph, &pop.deaths, |v| map_clamped(v as f32, 0.0, vmax, py + ph, py), col);
+    }
+}
+
+fn draw_graph_plants(x: f32, y: f32, w: f32, h: f32, hist: &StatsHistory) {
+    if hist.len() < 2 {
+        return;
+    }
+    let (px, py, pw, ph) = graph_plot_rect(x, y, w, h);
+    draw_y_ticks(px, py, pw, ph, 0.0, 1.0, fmt_small);
+    draw_series_f(px, py, pw, ph, &hist.plants, |v| map_clamped(v, 0.0, 1.0, py + ph, py), C_PLANT);
+}
+
+fn draw_graph_energy(x: f32, y: f32, w: f32, h: f32, hist: &StatsHistory, cols: &[Color]) {
+    if hist.len() < 2 {
+        return;
+    }
+    let vmax = hist.max_energy_recent().max(0.5) * 1.10;
+    let (px, py, pw, ph) = graph_plot_rect(x, y, w, h);
+    draw_y_ticks(px, py, pw, ph, 0.0, vmax, fmt_small);
+    for (pop, &col) in hist.pops.iter().zip(cols) {
+        draw_series_f(px, py, pw, ph, &pop.e, |v| map_clamped(v, 0.0, vmax, py + ph, py), col);
+    }
+}
+
+// mean line with a faint +-1 sd band, both relative to the baseline (1.0)
+fn draw_graph_traits(x: f32, y: f32, w: f32, h: f32, hist: &StatsHistory, cols: &[Color], k: usize) {
+    if hist.len() < 2 {
+        return;
+    }
+    let mut vmax = 1.5f32;
+    for pop in &hist.pops {
+        for t in pop.traits.iter() {
+            vmax = vmax.max(t[k].mean + t[k].sd);
+        }
+    }
+    vmax *= 1.10;
+
+    let (px, py, pw, ph) = graph_plot_rect(x, y, w, h);
+    draw_y_ticks(px, py, pw, ph, 0.0, vmax, fmt_small);
+
+    let to_y = |v: f32| map_clamped(v, 0.0, vmax, py + ph, py);
+    for (pop, &col) in hist.pops.iter().zip(cols) {
+        draw_trait_band(px, pw, &pop.traits, k, to_y, col);
+        let means: VecDeque<f32> = pop.traits.iter().map(|t| t[k].mean).collect();
+        draw_series_f(px, py, pw, ph, &means, to_y, col);
+    }
+}
+
+fn draw_trait_band<F: Fn(f32) -> f32>(x: f32, w: f32, data: &VecDeque<TraitStats>, k: usize, to_y: F, col: Color) {
+    let n = data.len();
+    let band = Color::new(col.r, col.g, col.b, 0.12);
+    let bw = (w / n as f32).max(1.0);
+    for (i, t) in data.iter().enumerate() {
+        // extinct populations report 0, skip them instead of drawing a spike
+        if t[k].mean <= 0.0 {
+            continue;
+        }
+        let xx = x + i as f32 / (n - 1) as f32 * w;
+        let y0 = to_y(t[k].mean + t[k].sd);
+        let y1 = to_y(t[k].mean - t[k].sd);
+        draw_rectangle(xx - bw * 0.5, y0, bw, y1 - y0, band);
+    }
+}
+
+fn map_clamped(v: f32, a0: f32, a1: f32, b0: f32, b1: f32) -> f32 {
+    let t = if (a1 - a0).abs() < 1e-6 { 0.0 } else { (v - a0) / (a1 - a0) };
+    let t = t.clamp(0.0, 1.0);
+    b0 + (b1 - b0) * t
+}
+
+fn draw_series_u<F: Fn(u32) -> f32>(x: f32, y: f32, w: f32, h: f32, data: &VecDeque<u32>, to_y: F, col: Color) {
+    let n = data.len();
+    if n < 2 {
+        return;
+    }
+
+    let mut prev = data[0];
+    for (i, &cur) in data.iter().enumerate().skip(1) {
+        let t0 = (i - 1) as f32 / (n - 1) as f32;
+        let t1 = i as f32 / (n - 1) as f32;
+
+        let x0 = x + t0 * w;
+        let x1 = x + t1 * w;
+
+        let y0 = to_y(prev).clamp(y, y + h);
+        let y1 = to_y(cur).clamp(y, y + h);
+
+        draw_line(x0, y0, x1, y1, 2.0, col);
+        prev = cur;
+    }
+}
+
+fn draw_series_u_dim<F: Fn(u32) -> f32>(x: f32, y: f32, w: f32, h: f32, data: &VecDeque<u32>, to_y: F, col: Color) {
+    let n = data.len();
+    if n < 2 {
+        return;
+    }
+
+    let dim = Color::new(col.r, col.g, col.b, 0.55);
+
+    let mut prev = data[0];
+    for (i, &cur) in data.iter().enumerate().skip(1) {
+        let t0 = (i - 1) as f32 / (n - 1) as f32;
+        let t1 = i as f32 / (n - 1) as f32;
+
+        let x0 = x + t0 * w;
+        let x1 = x + t1 * w;
+
+        let y0 = to_y(prev).clamp(y, y + h);
+        let y1 = to_y(cur).clamp(y, y + h);
+
+        draw_line(x0, y0, x1, y1, 2.0, dim);
+        prev = cur;
+    }
+}
+
+fn draw_series_f<F: Fn(f32) -> f32>(x: f32, y: f32, w: f32, h: f32, data: &VecDeque<f32>, to_y: F, col: Color) {
+    let n = data.len();
+    if n < 2 {
+        return;
+    }
+
+    let mut prev = data[0];
+    for (i, &cur) in data.iter().enumerate().skip(1) {
+        let t0 = (i - 1) as f32 / (n - 1) as f32;
+        let t1 = i as f32 / (n - 1) as f32;
+
+        let x0 = x + t0 * w;
+        let x1 = x + t1 * w;
+
+        let y0 = to_y(prev).clamp(y, y + h);
+        let y1 = to_y(cur).clamp(y, y + h);
+
+        draw_line(x0, y0, x1, y1, 2.0, col);
+        prev = cur;
+    }
+}
+
+fn row(x: f32, cy: &mut f32, k: &str, v: &str, c: Color) {
+    draw_text(k, x, *cy, 18.0, SUB);
+    draw_text(v, x + 150.0, *cy, 18.0, c);
+    *cy += 22.0;
+}
+
+fn row_color(x: f32, cy: &mut f32, k: &str, v: &str, c: Color) {
+    draw_text(k, x, *cy, 18.0, SUB);
+    draw_text(v, x + 150.0, *cy, 18.0, c);
+    *cy += 22.0;
+}
+
+fn draw_text_center(s: &str, cx: f32, cy: f32, sz: f32, col: Color) {
+    let m = measure_text(s, None, sz as u16, 1.0);
+    draw_text(s, cx - m.width * 0.5, cy, sz, col);
+}
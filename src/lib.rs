@@ -0,0 +1,18 @@
+pub mod config;
+pub mod edit;
+pub mod genome;
+pub mod lineage;
+pub mod par;
+pub mod replay;
+pub mod rewind;
+pub mod save;
+pub mod seasons;
+pub mod soil;
+pub mod spatial;
+pub mod species;
+pub mod stats;
+pub mod sweep;
+pub mod terrain;
+pub mod util;
+pub mod vec2;
+pub mod world;
@@ -0,0 +1,140 @@
+// who descends from whom. every agent gets a record when it's born and
+// keeps it after it dies for as long as some living agent descends from
+// it (or someone is looking at it), so the store stays about the size of
+// the living population plus the ancestry they share.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Record {
+    // 0 for the first generation and respawns, ids start at 1
+    pub parent: u32,
+    pub species: usize,
+    pub born: u64,
+    pub died: Option<u64>,
+    pub kids: Vec<u32>,
+}
+
+// one node of a descendant tree, in depth first order
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TreeNode {
+    pub id: u32,
+    pub depth: u32,
+    // index of the parent node, None for the root
+    pub up: Option<usize>,
+    pub species: usize,
+    pub born: u64,
+    pub died: Option<u64>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Lineage {
+    // ordered so saves and pruning come out the same every time
+    recs: BTreeMap<u32, Record>,
+}
+
+impl Lineage {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.recs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.recs.is_empty()
+    }
+
+    pub fn get(&self, id: u32) -> Option<&Record> {
+        self.recs.get(&id)
+    }
+
+    pub fn records(&self) -> impl Iterator<Item = (u32, &Record)> {
+        self.recs.iter().map(|(&id, r)| (id, r))
+    }
+
+    // back from (id, parent, species, born, died) rows, as saved
+    pub fn restore(mut rows: Vec<(u32, u32, usize, u64, Option<u64>)>) -> Self {
+        // kids are listed in birth order, which is id order
+        rows.sort_by_key(|r| r.0);
+        let mut l = Self::new();
+        for (id, parent, species, born, died) in rows {
+            l.born(id, parent, species, born);
+            if let Some(d) = died {
+                l.died(id, d);
+            }
+        }
+        l
+    }
+
+    pub fn born(&mut self, id: u32, parent: u32, species: usize, born: u64) {
+        self.recs.insert(id, Record { parent, species, born, died: None, kids: Vec::new() });
+        if let Some(p) = self.recs.get_mut(&parent) {
+            p.kids.push(id);
+        }
+    }
+
+    pub fn died(&mut self, id: u32, step: u64) {
+        if let Some(r) = self.recs.get_mut(&id) {
+            r.died = Some(step);
+        }
+    }
+
+    // drops the dead whose lines have all died out. `keep` and its whole
+    // family, up and down, stay regardless
+    pub fn prune(&mut self, keep: Option<u32>) {
+        let mut live = BTreeSet::new();
+        let mark_up = |live: &mut BTreeSet<u32>, recs: &BTreeMap<u32, Record>, mut id: u32| {
+            while let Some(r) = recs.get(&id) {
+                if !live.insert(id) {
+                    break;
+                }
+                id = r.parent;
+            }
+        };
+
+        for (&id, r) in &self.recs {
+            if r.died.is_none() {
+                mark_up(&mut live, &self.recs, id);
+            }
+        }
+        if let Some(k) = keep {
+            mark_up(&mut live, &self.recs, k);
+            live.extend(self.descendants(k));
+        }
+
+        self.recs.retain(|id, _| live.contains(id));
+        for r in self.recs.values_mut() {
+            r.kids.retain(|k| live.contains(k));
+        }
+    }
+
+    // everyone descended from id, not including it
+    pub fn descendants(&self, id: u32) -> Vec<u32> {
+        let mut out = Vec::new();
+        let mut stack: Vec<u32> = self.recs.get(&id).map(|r| r.kids.iter().rev().copied().collect()).unwrap_or_default();
+        while let Some(k) = stack.pop() {
+            out.push(k);
+            if let Some(r) = self.recs.get(&k) {
+                stack.extend(r.kids.iter().rev());
+            }
+        }
+        out
+    }
+
+    // the tree under id, root first, children in birth order
+    pub fn tree(&self, id: u32) -> Vec<TreeNode> {
+        let mut out = Vec::new();
+        let mut stack = vec![(id, 0u32, None)];
+        while let Some((k, depth, up)) = stack.pop() {
+            let Some(r) = self.recs.get(&k) else {
+                continue;
+            };
+            let at = out.len();
+            out.push(TreeNode { id: k, depth, up, species: r.species, born: r.born, died: r.died });
+            stack.extend(r.kids.iter().rev().map(|&c| (c, depth + 1, Some(at))));
+        }
+        out
+    }
+}
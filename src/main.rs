@@ -1,14 +1,23 @@
 use macroquad::prelude::*;
 
-mod config;
-mod ui;
-mod util;
-mod world;
-
-use config::*;
-use ui::*;
-use util::*;
-use world::*;
+mod gui;
+
+use ecosim::config::*;
+use ecosim::edit;
+use ecosim::replay::{self, Event, Replay};
+use ecosim::rewind::Rewind;
+use ecosim::save::RunState;
+use ecosim::stats::*;
+use ecosim::terrain::TerrainSource;
+use ecosim::util::*;
+use ecosim::world::*;
+use gui::config::*;
+use gui::life::LifeStory;
+use gui::playback::Player;
+use gui::render::WorldView;
+use gui::tools::{Tool, Tools};
+use gui::saves::*;
+use gui::ui::*;
 
 #[macroquad::main("EcoSim")]
 async fn main() {
@@ -21,18 +30,82 @@ async fn main() {
 
     let mut layout = Layout::compute(screen_width(), screen_height());
 
+    let mut ui = UiState::new();
+
+    // --settings wins, otherwise whatever the home screen last saved (w)
+    let set_path = cli_arg("--settings").unwrap_or_else(|| SETTINGS_FILE.to_string());
     let mut set = SimSettings::default();
-    let mut world = World::new(&mut rng, set);
+    if cli_arg("--settings").is_some() || std::path::Path::new(&set_path).exists() {
+        match SimSettings::load_file(&set_path) {
+            Ok(s) => {
+                set = s;
+                ui.log_push(format!("settings from {}", set_path));
+            }
+            Err(e) => {
+                eprintln!("settings: {}", e);
+                ui.log_push("settings file failed, using defaults".to_string());
+            }
+        }
+    }
+    if let Some((w, h)) = cli_size() {
+        set.world_w = w.clamp(MIN_DIM, MAX_DIM);
+        set.world_h = h.clamp(MIN_DIM, MAX_DIM);
+    }
+    if let Some(t) = cli_arg("--terrain") {
+        match TerrainSource::parse(&t) {
+            Ok(src) => set.terrain = src,
+            Err(e) => {
+                eprintln!("terrain: {}", e);
+                ui.log_push("terrain failed, keeping settings".to_string());
+            }
+        }
+    }
+    let mut world = World::new(&mut rng, &set);
 
     let tuning = SimTuning::default();
     let mut hist = StatsHistory::new();
-    let mut ui = UiState::new();
+    let mut rewind = Rewind::new(REWIND_EVERY, REWIND_KEEP);
 
     let mut acc = 0.0f32;
     let mut steps = 0u64;
     let mut speed = 1.0f32;
 
     let mut tracked: Option<TrackTarget> = None;
+    let mut life = LifeStory::new(None);
+
+    let mut rec_on = std::env::args().any(|a| a == "--record");
+    let mut rec: Option<CsvRecorder> = None;
+
+    let mut browser: Option<SaveBrowser> = None;
+    let mut view = WorldView::new();
+    let mut tools = Tools::new();
+
+    // everything done to the current run so far, None for runs loaded from
+    // a save since those don't start from a seed. rtick counts steps since
+    // it started, restarts included
+    let mut rlog: Option<Replay> = None;
+    let mut rtick = 0u64;
+    let mut rstart = 0.0f64;
+
+    let mut player: Option<Player> = None;
+    if let Some(path) = cli_arg("--replay") {
+        match Replay::read(&path) {
+            Ok(r) => {
+                set = r.set.clone();
+                seed = r.seed;
+                rng = rng_from_seed(seed);
+                world = World::new(&mut rng, &set);
+                player = Some(Player::new(r, get_time()));
+                ui.log_push(format!("replaying {}", path));
+                rec_restart(&mut rec, rec_on, &world, seed, steps, &mut ui);
+                mode = SimMode::Running;
+            }
+            Err(e) => {
+                eprintln!("replay: {}: {}", path, e);
+                ui.log_push(format!("replay failed: {}", e));
+            }
+        }
+    }
 
     loop {
         let expected_w = layout.world_w_px + layout.panel_w;
@@ -40,15 +113,144 @@ async fn main() {
             layout = Layout::compute(screen_width(), screen_height());
         }
 
+        // save browser is modal, the sim stays frozen underneath
+        if let Some(b) = browser.as_mut() {
+            clear_background(BG);
+            if let SimMode::Home = mode {
+                draw_home(&layout, &ui, &set, cpu_threads, seed);
+            } else {
+                view.draw(&world, &layout, 1.0, tracked);
+                let tinfo = tracked.and_then(|t| world.tracked_info(t));
+                draw_panel(&layout, &world, &hist, &rewind, &ui, mode, steps, seed, speed, &set, tinfo, &life);
+            }
+
+            let action = browser_input(b);
+            draw_browser(b);
+
+            match action {
+                BrowserAction::None => {}
+                BrowserAction::Close => browser = None,
+                BrowserAction::Save(path) => {
+                    let run = RunState { seed, steps, speed, rng: rng.clone() };
+                    ui.log_push(save_slot(&path, &world, &set, &run));
+                    b.name_buf.clear();
+                    b.refresh();
+                }
+                BrowserAction::Delete(path) => {
+                    if std::fs::remove_file(&path).is_ok() {
+                        ui.log_push("deleted save".to_string());
+                    }
+                    b.refresh();
+                }
+                BrowserAction::Load(path) => {
+                    match World::load(&path) {
+                        Ok(data) => {
+                            world = data.world;
+                            set = data.set;
+                            if let Some(run) = data.run {
+                                seed = run.seed;
+                                steps = run.steps;
+                                speed = run.speed;
+                                rng = run.rng;
+                            }
+                            hist = StatsHistory::new();
+                            rewind.clear();
+                            acc = 0.0;
+                            tracked = None;
+                            rlog = None;
+                            player = None;
+                            ui.log_push(if data.upgraded { "loaded (upgraded eco3)" } else { "loaded" }.to_string());
+                            rec_restart(&mut rec, rec_on, &world, seed, steps, &mut ui);
+                            mode = SimMode::Paused;
+                        }
+                        Err(e) => ui.log_push(format!("load failed: {}", e)),
+                    }
+                    browser = None;
+                }
+            }
+
+            next_frame().await;
+            continue;
+        }
+
         if is_key_pressed(KeyCode::Escape) {
             break;
         }
 
+        if let Some(p) = player.as_mut() {
+            while let Some(ev) = p.poll(rtick, matches!(mode, SimMode::Paused), get_time()) {
+                match ev {
+                    Event::Pause => mode = SimMode::Paused,
+                    Event::Resume => mode = SimMode::Running,
+                    Event::Speed(v) => speed = v,
+                    Event::Track(t) => tracked = t,
+                    _ => {
+                        if replay::apply(ev, &mut world, &mut rng, &mut seed, &set) {
+                            hist = StatsHistory::new();
+                            rewind.clear();
+                            ui.log.clear();
+                            acc = 0.0;
+                            steps = 0;
+                            tracked = None;
+                            ui.log_push(if let Event::NewSeed(_) = ev { "new seed" } else { "restart" }.to_string());
+                            rec_restart(&mut rec, rec_on, &world, seed, steps, &mut ui);
+                        }
+                    }
+                }
+            }
+            if p.done(rtick) {
+                player = None;
+                mode = SimMode::Paused;
+                ui.log_push("replay finished".to_string());
+            }
+        }
+        // replays drive the sim themselves, the keys below that change it are off
+        let live = player.is_none();
+
+        if !matches!(mode, SimMode::Home) {
+            view.input(&layout, world.space());
+            rewind.record(steps, rtick, seed, &world, &rng, rlog_len(&rlog));
+        }
+        if live
+            && !matches!(mode, SimMode::Home)
+            && let Some((i, fork)) = scrubber_input(&layout, &rewind, steps)
+            && let Some(snap) = rewind.rewind(i)
+        {
+            world = snap.world(&world);
+            rng = snap.rng.clone();
+            seed = snap.seed;
+            steps = snap.steps;
+            rtick = snap.tick;
+            // the replay forgets the undone steps, its clock picks up where they began
+            if let Some(r) = rlog.as_mut() {
+                let ms = r.truncate(rtick, snap.events);
+                rstart = get_time() - ms as f64 / 1000.0;
+            }
+            if fork {
+                seed = gen_seed(cpu_threads);
+                rng = rng_from_seed(seed);
+                rlog_push(&mut rlog, rtick, rstart, Event::Reseed(seed));
+                rewind.fork(seed, &rng, rlog_len(&rlog));
+                ui.log_push(format!("forked at step {}", steps));
+            } else {
+                ui.log_push(format!("back to step {}", steps));
+            }
+            hist.truncate_after(steps);
+            life = LifeStory::new(tracked);
+            acc = 0.0;
+            rec_restart(&mut rec, rec_on, &world, seed, steps, &mut ui);
+        }
+
         if is_key_pressed(KeyCode::Equal) || is_key_pressed(KeyCode::KpAdd) {
             speed = (speed * 1.25).min(8.0);
+            rlog_push(&mut rlog, rtick, rstart, Event::Speed(speed));
         }
         if is_key_pressed(KeyCode::Minus) || is_key_pressed(KeyCode::KpSubtract) {
             speed = (speed / 1.25).max(0.10);
+            rlog_push(&mut rlog, rtick, rstart, Event::Speed(speed));
+        }
+        if is_key_pressed(KeyCode::T) {
+            ui.trait_sel = (ui.trait_sel + 1) % ecosim::genome::TRAIT_COUNT;
         }
 
         clear_background(BG);
@@ -69,37 +271,81 @@ async fn main() {
                     ui.seed_buf.clear();
                 }
 
-                draw_home(&layout, &ui, set, cpu_threads, seed);
+                draw_home(&layout, &ui, &set, cpu_threads, seed);
+
+                if is_key_pressed(KeyCode::L) {
+                    browser = Some(SaveBrowser::open(false));
+                }
+
+                if is_key_pressed(KeyCode::O) {
+                    match SimSettings::load_file(&set_path) {
+                        Ok(s) => {
+                            set = s;
+                            ui.log_push(format!("settings from {}", set_path));
+                        }
+                        Err(e) => ui.log_push(format!("settings: {}", e)),
+                    }
+                }
+                if is_key_pressed(KeyCode::W) {
+                    match std::fs::write(&set_path, set.to_text()) {
+                        Ok(()) => ui.log_push(format!("settings saved to {}", set_path)),
+                        Err(e) => ui.log_push(format!("settings: {}: {}", set_path, e)),
+                    }
+                }
 
                 if is_key_pressed(KeyCode::Enter) {
-                    world = World::new(&mut rng, set);
+                    world = World::new(&mut rng, &set);
                     hist = StatsHistory::new();
+                    rewind.clear();
                     ui.log.clear();
                     ui.seed_buf.clear();
                     acc = 0.0;
                     steps = 0;
                     tracked = None;
+                    rlog = Some(Replay::new(seed, &set));
+                    rtick = 0;
+                    rstart = get_time();
+                    rec_restart(&mut rec, rec_on, &world, seed, steps, &mut ui);
                     mode = SimMode::Running;
                 }
             }
             SimMode::Running => {
-                handle_pick(&layout, &world, &mut tracked);
+                if live
+                    && let Some((ed, start)) = tools.input(&layout, &view, &world)
+                {
+                    rlog_push(&mut rlog, rtick, rstart, Event::Edit(ed));
+                    let msg = edit::apply(&mut world, ed);
+                    if start {
+                        ui.log_push(msg);
+                    }
+                    life.take_events(steps, &mut world);
+                }
+                if tools.tool == Tool::Track && !tools.over_palette() && handle_pick(&layout, &view, &world, &mut tracked) {
+                    rlog_push(&mut rlog, rtick, rstart, Event::Track(tracked));
+                }
 
                 if is_key_pressed(KeyCode::S) {
-                    let ok = world.save(SAVE_PATH, set);
-                    ui.log_push(if ok { "saved".to_string() } else { "save failed".to_string() });
+                    let run = RunState { seed, steps, speed, rng: rng.clone() };
+                    ui.log_push(save_slot(&quick_save_path(), &world, &set, &run));
                 }
                 if is_key_pressed(KeyCode::L) {
-                    if let Some((w, s2)) = World::load(SAVE_PATH) {
-                        world = w;
-                        set = s2;
-                        hist = StatsHistory::new();
-                        acc = 0.0;
-                        tracked = None;
-                        ui.log_push("loaded".to_string());
-                    } else {
-                        ui.log_push("load failed".to_string());
-                    }
+                    mode = SimMode::Paused;
+                    browser = Some(SaveBrowser::open(true));
+                }
+
+                if is_key_pressed(KeyCode::C) {
+                    rec_on = rec_toggle(&mut rec, &world, seed, steps, &mut ui);
+                }
+                if is_key_pressed(KeyCode::V) {
+                    ui.log_push(replay_write(&mut rlog, rtick));
+                }
+
+                // the world logs the tracked agent's life as it steps
+                if world.watching() != tracked {
+                    world.watch(tracked);
+                }
+                if life.target != tracked {
+                    life = LifeStory::new(tracked);
                 }
 
                 let frame_dt = get_frame_time().min(0.10);
@@ -107,16 +353,20 @@ async fn main() {
 
                 let mut n = 0u32;
                 let mut did = false;
-                let mut last_d = Deltas {
-                    herb_birth: 0,
-                    herb_death: 0,
-                    pred_birth: 0,
-                    pred_death: 0,
-                };
+                let mut last_d = Deltas::default();
 
                 while acc >= tuning.fixed_dt && n < tuning.max_steps_per_frame {
-                    last_d = world.step(&mut rng, set, tuning.fixed_dt);
+                    // events due at this step go out next frame, before it runs
+                    if player.as_ref().is_some_and(|p| p.blocks(rtick)) {
+                        acc = 0.0;
+                        break;
+                    }
+                    last_d = world.step(&mut rng, &set, tuning.fixed_dt);
                     steps += 1;
+                    rtick += 1;
+                    rewind.record(steps, rtick, seed, &world, &rng, rlog_len(&rlog));
+                    life.update(steps, &mut world);
+                    rec_push(&mut rec, steps, &world.counts(), &last_d, &mut ui);
                     acc -= tuning.fixed_dt;
                     n += 1;
                     did = true;
@@ -124,94 +374,135 @@ async fn main() {
 
                 let alpha = (acc / tuning.fixed_dt).clamp(0.0, 1.0);
 
-                world.draw(&layout, alpha, tracked);
+                view.draw(&world, &layout, alpha, tracked);
+                if let Some(t) = tracked.filter(|_| view.family) {
+                    draw_family_tree(&layout, &world, t.id);
+                }
+                if live {
+                    tools.draw(&layout, &view, &world);
+                }
 
                 if did {
                     let c = world.counts();
-                    hist.push(steps, c, last_d);
-                    tick_events(&mut ui, c, last_d);
+                    hist.push(steps, &c, &last_d);
+                    tick_events(&mut ui, &world, &c);
                 }
 
+                ui.recording = rec.is_some();
                 let tinfo = tracked.and_then(|t| world.tracked_info(t));
-                draw_panel(&layout, &world, &hist, &ui, mode, steps, seed, speed, set, tinfo);
+                draw_panel(&layout, &world, &hist, &rewind, &ui, mode, steps, seed, speed, &set, tinfo, &life);
 
                 if is_key_pressed(KeyCode::Space) {
                     mode = SimMode::Paused;
+                    rlog_push(&mut rlog, rtick, rstart, Event::Pause);
                 }
-                if is_key_pressed(KeyCode::R) {
-                    world = World::new(&mut rng, set);
+                if live && is_key_pressed(KeyCode::R) {
+                    rlog_push(&mut rlog, rtick, rstart, Event::Restart);
+                    world = World::new(&mut rng, &set);
                     hist = StatsHistory::new();
+                    rewind.clear();
                     ui.log.clear();
                     acc = 0.0;
                     steps = 0;
                     tracked = None;
                     ui.log_push("restart".to_string());
+                    rec_restart(&mut rec, rec_on, &world, seed, steps, &mut ui);
                 }
-                if is_key_pressed(KeyCode::N) {
+                if live && is_key_pressed(KeyCode::N) {
                     seed = gen_seed(cpu_threads);
+                    rlog_push(&mut rlog, rtick, rstart, Event::NewSeed(seed));
                     rng = rng_from_seed(seed);
-                    world = World::new(&mut rng, set);
+                    world = World::new(&mut rng, &set);
                     hist = StatsHistory::new();
+                    rewind.clear();
                     ui.log.clear();
                     acc = 0.0;
                     steps = 0;
                     tracked = None;
                     ui.log_push("new seed".to_string());
+                    rec_restart(&mut rec, rec_on, &world, seed, steps, &mut ui);
                 }
             }
             SimMode::Paused => {
-                handle_pick(&layout, &world, &mut tracked);
+                if live
+                    && let Some((ed, start)) = tools.input(&layout, &view, &world)
+                {
+                    rlog_push(&mut rlog, rtick, rstart, Event::Edit(ed));
+                    let msg = edit::apply(&mut world, ed);
+                    if start {
+                        ui.log_push(msg);
+                    }
+                    life.take_events(steps, &mut world);
+                }
+                if tools.tool == Tool::Track && !tools.over_palette() && handle_pick(&layout, &view, &world, &mut tracked) {
+                    rlog_push(&mut rlog, rtick, rstart, Event::Track(tracked));
+                }
 
                 if is_key_pressed(KeyCode::S) {
-                    let ok = world.save(SAVE_PATH, set);
-                    ui.log_push(if ok { "saved".to_string() } else { "save failed".to_string() });
+                    let run = RunState { seed, steps, speed, rng: rng.clone() };
+                    ui.log_push(save_slot(&quick_save_path(), &world, &set, &run));
                 }
                 if is_key_pressed(KeyCode::L) {
-                    if let Some((w, s2)) = World::load(SAVE_PATH) {
-                        world = w;
-                        set = s2;
-                        hist = StatsHistory::new();
-                        acc = 0.0;
-                        tracked = None;
-                        ui.log_push("loaded".to_string());
-                    } else {
-                        ui.log_push("load failed".to_string());
-                    }
+                    browser = Some(SaveBrowser::open(true));
                 }
 
-                world.draw(&layout, 1.0, tracked);
+                if is_key_pressed(KeyCode::C) {
+                    rec_on = rec_toggle(&mut rec, &world, seed, steps, &mut ui);
+                }
+                if is_key_pressed(KeyCode::V) {
+                    ui.log_push(replay_write(&mut rlog, rtick));
+                }
 
+                view.draw(&world, &layout, 1.0, tracked);
+                if let Some(t) = tracked.filter(|_| view.family) {
+                    draw_family_tree(&layout, &world, t.id);
+                }
+                if live {
+                    tools.draw(&layout, &view, &world);
+                }
+
+                ui.recording = rec.is_some();
                 let tinfo = tracked.and_then(|t| world.tracked_info(t));
-                draw_panel(&layout, &world, &hist, &ui, mode, steps, seed, speed, set, tinfo);
+                draw_panel(&layout, &world, &hist, &rewind, &ui, mode, steps, seed, speed, &set, tinfo, &life);
 
                 draw_pause_overlay(&layout);
 
                 if is_key_pressed(KeyCode::Space) {
                     mode = SimMode::Running;
+                    rlog_push(&mut rlog, rtick, rstart, Event::Resume);
                 }
-                if is_key_pressed(KeyCode::R) {
-                    world = World::new(&mut rng, set);
+                if live && is_key_pressed(KeyCode::R) {
+                    rlog_push(&mut rlog, rtick, rstart, Event::Restart);
+                    rlog_push(&mut rlog, rtick, rstart, Event::Resume);
+                    world = World::new(&mut rng, &set);
                     hist = StatsHistory::new();
+                    rewind.clear();
                     ui.log.clear();
                     acc = 0.0;
                     steps = 0;
                     tracked = None;
                     ui.log_push("restart".to_string());
+                    rec_restart(&mut rec, rec_on, &world, seed, steps, &mut ui);
                     mode = SimMode::Running;
                 }
-                if is_key_pressed(KeyCode::N) {
+                if live && is_key_pressed(KeyCode::N) {
                     seed = gen_seed(cpu_threads);
+                    rlog_push(&mut rlog, rtick, rstart, Event::NewSeed(seed));
+                    rlog_push(&mut rlog, rtick, rstart, Event::Resume);
                     rng = rng_from_seed(seed);
-                    world = World::new(&mut rng, set);
+                    world = World::new(&mut rng, &set);
                     hist = StatsHistory::new();
+                    rewind.clear();
                     ui.log.clear();
                     acc = 0.0;
                     steps = 0;
                     tracked = None;
                     ui.log_push("new seed".to_string());
+                    rec_restart(&mut rec, rec_on, &world, seed, steps, &mut ui);
                     mode = SimMode::Running;
                 }
                 if is_key_pressed(KeyCode::Enter) {
+                    player = None;
                     mode = SimMode::Home;
                 }
             }
@@ -219,44 +510,143 @@ async fn main() {
 
         next_frame().await;
     }
+
+    if let Some(r) = rec {
+        let _ = r.finish();
+    }
 }
 
-fn handle_pick(layout: &Layout, world: &World, tracked: &mut Option<TrackTarget>) {
+// true if the click changed what's tracked
+fn handle_pick(layout: &Layout, view: &WorldView, world: &World, tracked: &mut Option<TrackTarget>) -> bool {
     if is_mouse_button_pressed(MouseButton::Left) {
         let (mx, my) = mouse_position();
         if mx >= 0.0 && mx < layout.world_w_px && my >= 0.0 && my < layout.world_h_px {
-            let wp = vec2(mx / CELL, my / CELL);
-            *tracked = world.pick_target(wp);
+            let t = view.screen_to_world(layout, world.space(), mx, my).and_then(|wp| world.pick_target(wp));
+            if t != *tracked {
+                *tracked = t;
+                return true;
+            }
         }
     }
+    false
 }
 
-fn tick_events(ui: &mut UiState, c: Counts, d: Deltas) {
+// tags: 1 + species index for an extinction, then plant bloom / crash
+fn tick_events(ui: &mut UiState, world: &World, c: &Counts) {
     let mut tag = 0u8;
 
-    if c.herbs == 0 {
-        tag = 1;
-        if ui.last_tag != tag {
-            ui.log_push("herb extinction".to_string());
-        }
-    } else if c.preds == 0 {
-        tag = 2;
+    if let Some(s) = c.pops.iter().position(|p| p.n == 0) {
+        tag = 1 + s as u8;
         if ui.last_tag != tag {
-            ui.log_push("pred extinction".to_string());
+            ui.log_push(format!("{} extinction", world.species()[s].def.name));
         }
     } else if c.plants_avg > 0.82 {
-        tag = 3;
+        tag = 200;
         if ui.last_tag != tag {
             ui.log_push("plant bloom".to_string());
         }
     } else if c.plants_avg < 0.18 {
-        tag = 4;
+        tag = 201;
         if ui.last_tag != tag {
             ui.log_push("plant crash".to_string());
         }
     }
 
     ui.last_tag = tag;
+}
+
+// value after a `--flag value` pair
+fn cli_arg(flag: &str) -> Option<String> {
+    let args: Vec<String> = std::env::args().collect();
+    let i = args.iter().position(|a| a == flag)?;
+    args.get(i + 1).cloned()
+}
 
-    let _ = d;
+fn cli_size() -> Option<(i32, i32)> {
+    match parse_size(&cli_arg("--size")?) {
+        Ok(wh) => Some(wh),
+        Err(e) => {
+            eprintln!("{}", e);
+            None
+        }
+    }
+}
+
+fn save_slot(path: &str, world: &World, set: &SimSettings, run: &RunState) -> String {
+    if let Err(e) = std::fs::create_dir_all(SAVE_DIR) {
+        return format!("save failed: {}", e);
+    }
+    match world.save(path, set, run) {
+        Ok(()) => format!("saved {}", path),
+        Err(e) => format!("save failed: {}", e),
+    }
+}
+
+fn rlog_push(rlog: &mut Option<Replay>, tick: u64, start: f64, ev: Event) {
+    if let Some(r) = rlog.as_mut() {
+        let ms = ((get_time() - start) * 1000.0).max(0.0) as u64;
+        r.push(tick, ms, ev);
+    }
+}
+
+fn rlog_len(rlog: &Option<Replay>) -> usize {
+    rlog.as_ref().map_or(0, |r| r.events.len())
+}
+
+// writes the run so far, the log keeps going
+fn replay_write(rlog: &mut Option<Replay>, tick: u64) -> String {
+    let Some(r) = rlog.as_mut() else {
+        return "no replay, only runs started from the home screen keep one".to_string();
+    };
+    r.end = tick;
+    let path = format!("ecosim_{}_{}.replay", r.seed, tick);
+    match r.write(&path) {
+        Ok(()) => format!("replay {}", path),
+        Err(e) => format!("replay failed: {}", e),
+    }
+}
+
+fn rec_path(seed: u64, steps: u64) -> String {
+    format!("ecosim_{}_{}.csv", seed, steps)
+}
+
+// starts a fresh csv for a new run if recording is on
+fn rec_restart(rec: &mut Option<CsvRecorder>, on: bool, world: &World, seed: u64, steps: u64, ui: &mut UiState) {
+    if let Some(r) = rec.take() {
+        let _ = r.finish();
+    }
+    if !on {
+        return;
+    }
+
+    let path = rec_path(seed, steps);
+    match CsvRecorder::create(&path, &world.species_names()) {
+        Ok(r) => {
+            ui.log_push(format!("recording {}", path));
+            *rec = Some(r);
+        }
+        Err(_) => ui.log_push("record failed".to_string()),
+    }
+}
+
+fn rec_toggle(rec: &mut Option<CsvRecorder>, world: &World, seed: u64, steps: u64, ui: &mut UiState) -> bool {
+    if let Some(r) = rec.take() {
+        match r.finish() {
+            Ok(n) => ui.log_push(format!("recorded {} rows", n)),
+            Err(_) => ui.log_push("record failed".to_string()),
+        }
+        return false;
+    }
+
+    rec_restart(rec, true, world, seed, steps, ui);
+    rec.is_some()
+}
+
+fn rec_push(rec: &mut Option<CsvRecorder>, steps: u64, c: &Counts, d: &Deltas, ui: &mut UiState) {
+    if let Some(r) = rec.as_mut()
+        && r.push(steps, c, d).is_err()
+    {
+        *rec = None;
+        ui.log_push("record failed".to_string());
+    }
 }
@@ -0,0 +1,34 @@
+// splits per-item work over scoped threads. callers only hand in work
+// where each item depends on its own data, so the split can't change
+// the result, just how long it takes.
+
+// `threads` setting -> worker count, 0 means one per cpu
+pub fn threads_for(setting: u32) -> usize {
+    match setting {
+        0 => num_cpus::get().max(1),
+        n => n as usize,
+    }
+}
+
+// runs f(offset, chunk) over contiguous chunks of items. below
+// min_chunk items per thread it's not worth spawning, so it stays inline.
+pub fn for_each_chunk<T: Send>(items: &mut [T], threads: usize, min_chunk: usize, f: impl Fn(usize, &mut [T]) + Sync) {
+    let n = items.len();
+    let parts = threads.min(n / min_chunk.max(1)).max(1);
+    if parts == 1 {
+        f(0, items);
+        return;
+    }
+
+    let size = n.div_ceil(parts);
+    std::thread::scope(|s| {
+        let mut chunks = items.chunks_mut(size).enumerate();
+        // the calling thread takes the first chunk itself
+        let (_, first) = chunks.next().unwrap_or_default();
+        for (k, chunk) in chunks {
+            let f = &f;
+            s.spawn(move || f(k * size, chunk));
+        }
+        f(0, first);
+    });
+}
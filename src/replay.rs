@@ -0,0 +1,303 @@
+// replays: a run's seed and settings plus everything the user did to it,
+// each event pinned to the sim step it happened before. steps are all the
+// sim knows about time, so playing the events back at the same steps gives
+// the same run at any frame rate.
+//
+// file layout, same conventions as saves (see save.rs):
+//
+//   "ECRP" u32 version
+//   sections until "END ": [u8; 4] tag, u32 len, len bytes
+//
+//   RPLY  u64 seed, u64 end tick
+//   SETT  settings text
+//   EVNT  u32 n, n x (u64 tick, u64 ms, u8 kind, payload)
+//
+// edits (kind 8) carry u8 tool, f32 x, f32 y, then for plants f32 r,
+// i32 amount, for spawn u32 species, f32 energy, for remove f32 r.
+//
+// unknown sections are skipped, an unknown event kind is an error since
+// skipping it could change the run.
+
+use crate::config::SimSettings;
+use crate::edit::{self, Edit};
+use crate::save::{Reader, SaveError, put_f32, put_i32, put_u32, put_u64, section};
+use crate::stats::Deltas;
+use crate::util::{SimRng, rng_from_seed};
+use crate::vec2::vec2;
+use crate::world::{TrackTarget, World};
+
+pub const REPLAY_VERSION: u32 = 1;
+
+const MAGIC: &[u8; 4] = b"ECRP";
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Event {
+    Pause,
+    Resume,
+    Speed(f32),
+    // new world from the same rng stream, like R
+    Restart,
+    // new world from a fresh seed, like N
+    NewSeed(u64),
+    Track(Option<TrackTarget>),
+    // same world, fresh rng from this seed. forking from a rewind does it
+    Reseed(u64),
+    Edit(Edit),
+}
+
+impl Event {
+    // whether it changes the sim, the rest only changes what's on screen
+    pub fn affects_sim(self) -> bool {
+        matches!(self, Event::Restart | Event::NewSeed(_) | Event::Reseed(_) | Event::Edit(_))
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Timed {
+    // steps since the replay started, the event applies before the next one
+    pub tick: u64,
+    // wall clock ms since the replay started, only used to hold pauses
+    pub ms: u64,
+    pub ev: Event,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Replay {
+    pub seed: u64,
+    pub set: SimSettings,
+    pub events: Vec<Timed>,
+    // steps recorded
+    pub end: u64,
+}
+
+impl Replay {
+    pub fn new(seed: u64, set: &SimSettings) -> Self {
+        Self { seed, set: set.clone(), events: Vec::new(), end: 0 }
+    }
+
+    pub fn push(&mut self, tick: u64, ms: u64, ev: Event) {
+        self.events.push(Timed { tick, ms, ev });
+    }
+
+    // keeps the first `events` and ends at `tick`, for when the run is
+    // rewound to a snapshot (see rewind.rs). returns the ms of the last
+    // event kept so the clock can carry on from it
+    pub fn truncate(&mut self, tick: u64, events: usize) -> u64 {
+        self.events.truncate(events);
+        self.end = tick;
+        self.events.last().map_or(0, |t| t.ms)
+    }
+
+    pub fn write(&self, path: &str) -> Result<(), SaveError> {
+        let mut out = Vec::new();
+        out.extend_from_slice(MAGIC);
+        put_u32(&mut out, REPLAY_VERSION);
+
+        let mut b = Vec::new();
+        put_u64(&mut b, self.seed);
+        put_u64(&mut b, self.end);
+        section(&mut out, b"RPLY", &b);
+
+        section(&mut out, b"SETT", self.set.to_text().as_bytes());
+
+        let mut b = Vec::new();
+        put_u32(&mut b, self.events.len() as u32);
+        for t in &self.events {
+            put_u64(&mut b, t.tick);
+            put_u64(&mut b, t.ms);
+            match t.ev {
+                Event::Pause => b.push(0),
+                Event::Resume => b.push(1),
+                Event::Speed(v) => {
+                    b.push(2);
+                    put_f32(&mut b, v);
+                }
+                Event::Restart => b.push(3),
+                Event::NewSeed(s) => {
+                    b.push(4);
+                    put_u64(&mut b, s);
+                }
+                Event::Track(Some(tt)) => {
+                    b.push(5);
+                    put_u32(&mut b, tt.species as u32);
+                    put_u32(&mut b, tt.id);
+                }
+                Event::Track(None) => b.push(6),
+                Event::Reseed(s) => {
+                    b.push(7);
+                    put_u64(&mut b, s);
+                }
+                Event::Edit(ed) => {
+                    b.push(8);
+                    put_edit(&mut b, ed);
+                }
+            }
+        }
+        section(&mut out, b"EVNT", &b);
+
+        section(&mut out, b"END ", &[]);
+        std::fs::write(path, &out)?;
+        Ok(())
+    }
+
+    pub fn read(path: &str) -> Result<Replay, SaveError> {
+        let buf = std::fs::read(path)?;
+        let mut r = Reader { buf: &buf, pos: 0 };
+
+        if r.take(4).map_err(|_| SaveError::BadMagic)? != MAGIC {
+            return Err(SaveError::BadMagic);
+        }
+        let version = r.u32()?;
+        if version > REPLAY_VERSION {
+            return Err(SaveError::NewerVersion(version));
+        }
+
+        let mut head = None;
+        let mut set = None;
+        let mut events = Vec::new();
+
+        loop {
+            let tag: [u8; 4] = r.take(4)?.try_into().unwrap_or_default();
+            let len = r.u32()? as usize;
+            let mut s = Reader { buf: r.take(len)?, pos: 0 };
+
+            match &tag {
+                b"RPLY" => head = Some((s.u64()?, s.u64()?)),
+                b"SETT" => {
+                    let text = std::str::from_utf8(s.buf).map_err(|_| SaveError::Corrupt("settings"))?;
+                    set = Some(SimSettings::from_text_lenient(text).map_err(SaveError::BadSettings)?);
+                }
+                b"EVNT" => {
+                    let n = s.u32()?;
+                    for _ in 0..n {
+                        let (tick, ms) = (s.u64()?, s.u64()?);
+                        let ev = match s.u8()? {
+                            0 => Event::Pause,
+                            1 => Event::Resume,
+                            2 => Event::Speed(s.f32()?),
+                            3 => Event::Restart,
+                            4 => Event::NewSeed(s.u64()?),
+                            5 => Event::Track(Some(TrackTarget { species: s.u32()? as usize, id: s.u32()? })),
+                            6 => Event::Track(None),
+                            7 => Event::Reseed(s.u64()?),
+                            8 => Event::Edit(read_edit(&mut s)?),
+                            _ => return Err(SaveError::Corrupt("replay event")),
+                        };
+                        events.push(Timed { tick, ms, ev });
+                    }
+                }
+                b"END " => break,
+                _ => {}
+            }
+        }
+
+        let (seed, end) = head.ok_or(SaveError::MissingSection("RPLY"))?;
+        let set = set.ok_or(SaveError::MissingSection("SETT"))?;
+        if events.windows(2).any(|w| w[1].tick < w[0].tick) {
+            return Err(SaveError::Corrupt("replay event order"));
+        }
+        // watching a species that isn't there would index past the lists
+        if events.iter().any(|t| matches!(t.ev, Event::Track(Some(tt)) if tt.species >= set.species.len())) {
+            return Err(SaveError::Corrupt("replay track target"));
+        }
+        Ok(Replay { seed, set, events, end })
+    }
+}
+
+fn put_edit(b: &mut Vec<u8>, ed: Edit) {
+    let (tool, at) = match ed {
+        Edit::Plants { at, .. } => (0, at),
+        Edit::Spawn { at, .. } => (1, at),
+        Edit::Remove { at, .. } => (2, at),
+    };
+    b.push(tool);
+    put_f32(b, at.x);
+    put_f32(b, at.y);
+    match ed {
+        Edit::Plants { r, amount, .. } => {
+            put_f32(b, r);
+            put_i32(b, amount);
+        }
+        Edit::Spawn { species, e, .. } => {
+            put_u32(b, species as u32);
+            put_f32(b, e);
+        }
+        Edit::Remove { r, .. } => put_f32(b, r),
+    }
+}
+
+fn read_edit(s: &mut Reader) -> Result<Edit, SaveError> {
+    let tool = s.u8()?;
+    let at = vec2(s.f32()?, s.f32()?);
+    Ok(match tool {
+        0 => Edit::Plants { at, r: s.f32()?, amount: s.i32()? },
+        1 => Edit::Spawn { at, species: s.u32()? as usize, e: s.f32()? },
+        2 => Edit::Remove { at, r: s.f32()? },
+        _ => return Err(SaveError::Corrupt("replay edit")),
+    })
+}
+
+// applies a sim event, true if it made a new world (reseeding doesn't)
+pub fn apply(ev: Event, world: &mut World, rng: &mut SimRng, seed: &mut u64, set: &SimSettings) -> bool {
+    match ev {
+        Event::Restart => {
+            *world = World::new(rng, set);
+            true
+        }
+        Event::NewSeed(s) => {
+            *seed = s;
+            *rng = rng_from_seed(s);
+            *world = World::new(rng, set);
+            true
+        }
+        Event::Reseed(s) => {
+            *seed = s;
+            *rng = rng_from_seed(s);
+            false
+        }
+        Event::Edit(ed) => {
+            edit::apply(world, ed);
+            false
+        }
+        _ => false,
+    }
+}
+
+// plays a replay back without a window, only the sim events matter here
+pub struct Playback<'a> {
+    pub replay: &'a Replay,
+    pub world: World,
+    pub rng: SimRng,
+    pub seed: u64,
+    pub tick: u64,
+    next: usize,
+}
+
+impl<'a> Playback<'a> {
+    pub fn new(replay: &'a Replay) -> Self {
+        let mut rng = rng_from_seed(replay.seed);
+        let world = World::new(&mut rng, &replay.set);
+        Self { replay, world, rng, seed: replay.seed, tick: 0, next: 0 }
+    }
+
+    // events due before the next step, in order. the caller applies them
+    pub fn due(&mut self) -> &'a [Timed] {
+        let ev = &self.replay.events[self.next..];
+        let n = ev.iter().take_while(|t| t.tick <= self.tick).count();
+        self.next += n;
+        &ev[..n]
+    }
+
+    pub fn done(&self) -> bool {
+        self.tick >= self.replay.end
+    }
+
+    // applies whatever is due, then steps once
+    pub fn step(&mut self, dt: f32) -> Deltas {
+        for t in self.due() {
+            apply(t.ev, &mut self.world, &mut self.rng, &mut self.seed, &self.replay.set);
+        }
+        self.tick += 1;
+        self.world.step(&mut self.rng, &self.replay.set, dt)
+    }
+}
@@ -0,0 +1,104 @@
+// snapshots of the running world every few steps, for jumping back in
+// time. only what the next step reads is kept (plants, agents, ids, rng)
+// plus the family records, so going back and stepping on gives exactly
+// the run you came from. terrain never changes, it comes from the world
+// being restored into.
+
+use std::collections::VecDeque;
+
+use crate::lineage::Lineage;
+use crate::util::SimRng;
+use crate::world::{Agent, World};
+
+pub struct Snapshot {
+    // step count as shown, since the last (re)start
+    pub steps: u64,
+    // replay tick, see replay.rs
+    pub tick: u64,
+    pub seed: u64,
+    pub rng: SimRng,
+    // replay events logged when it was taken. edits made while paused on
+    // this step come after it, so going back cuts the log here and not by tick
+    pub events: usize,
+    plants: Vec<u8>,
+    soil: Vec<u8>,
+    pops: Vec<Vec<Agent>>,
+    next_id: u32,
+    now: u64,
+    lineage: Lineage,
+}
+
+impl Snapshot {
+    // `like` supplies what never changes during a run (size, species)
+    pub fn world(&self, like: &World) -> World {
+        let mut w = World::from_parts(like.space, self.plants.clone(), like.ground.clone(), like.species.clone(), self.pops.clone(), self.next_id);
+        w.now = self.now;
+        w.soil = self.soil.clone();
+        w.lineage = self.lineage.clone();
+        w
+    }
+}
+
+// ring of the last `cap` snapshots, one every `every` steps
+pub struct Rewind {
+    pub every: u64,
+    cap: usize,
+    snaps: VecDeque<Snapshot>,
+}
+
+impl Rewind {
+    pub fn new(every: u64, cap: usize) -> Self {
+        Self { every: every.max(1), cap: cap.max(1), snaps: VecDeque::new() }
+    }
+
+    pub fn clear(&mut self) {
+        self.snaps.clear();
+    }
+
+    pub fn snaps(&self) -> &VecDeque<Snapshot> {
+        &self.snaps
+    }
+
+    // call after every step (and at the start of a run), snapshots when due
+    pub fn record(&mut self, steps: u64, tick: u64, seed: u64, world: &World, rng: &SimRng, events: usize) {
+        if !steps.is_multiple_of(self.every) || self.snaps.back().is_some_and(|s| s.steps >= steps) {
+            return;
+        }
+        if self.snaps.len() == self.cap {
+            self.snaps.pop_front();
+        }
+        self.snaps.push_back(Snapshot {
+            steps,
+            tick,
+            seed,
+            rng: rng.clone(),
+            events,
+            plants: world.plants.clone(),
+            soil: world.soil.clone(),
+            pops: world.pops.clone(),
+            next_id: world.next_id,
+            now: world.now,
+            lineage: world.lineage.clone(),
+        });
+    }
+
+    // goes back to snapshot i. the ones after it describe a future that
+    // may not happen anymore, so they go
+    pub fn rewind(&mut self, i: usize) -> Option<&Snapshot> {
+        if i >= self.snaps.len() {
+            return None;
+        }
+        self.snaps.truncate(i + 1);
+        self.snaps.back()
+    }
+
+    // forking swaps the rng of the snapshot just rewound to, so going back
+    // there again lands on the fork and not the run it left
+    pub fn fork(&mut self, seed: u64, rng: &SimRng, events: usize) {
+        if let Some(s) = self.snaps.back_mut() {
+            s.seed = seed;
+            s.rng = rng.clone();
+            s.events = events;
+        }
+    }
+}
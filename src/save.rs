@@ -0,0 +1,535 @@
+use std::fmt;
+
+use rand09::SeedableRng;
+
+use crate::config::*;
+use crate::genome::{Genome, TRAIT_COUNT};
+use crate::lineage::Lineage;
+use crate::species;
+use crate::terrain::Ground;
+use crate::util::SimRng;
+use crate::vec2::vec2;
+use crate::world::{Agent, Space, World};
+
+// save file layout (all numbers little endian):
+//
+//   "ECOS" u32 version
+//   then sections until "END ": [u8; 4] tag, u32 len, len bytes of payload
+//
+// sections:
+//   META  u64 unix time, u64 seed, u64 steps, u32 first species count, u32 second
+//         species count, f32 plants avg, u32 n, n x u32 count per species
+//         (written first so save lists don't have to parse the rest)
+//   SETT  settings as `key = value` text (see SimSettings::to_text)
+//   WRLD  i32 w, i32 h, u32 next_id, u64 world step
+//   PLNT  u32 n, n x u8 plant density
+//   TERR  u32 n, n x u8 ground kind (see terrain.rs), all plain when missing
+//   SOIL  u32 n, n x u8 soil nutrients (see soil.rs), soil_start when missing
+//   AGNT  u32 kind (species index in SETT), u32 n, u32 stride, n records of `stride` bytes
+//   LINE  u32 n, n x (u32 id, u32 parent, u32 species, u64 born, u64 died or u64::MAX)
+//   RUN   u64 seed, u64 steps, f32 speed, rng: [u8; 32] key, u64 stream, u128 word pos
+//
+// readers skip sections they don't know and ignore trailing bytes in agent
+// records, so new sections / fields can be appended without a version bump.
+// the version only goes up for changes old readers can't skip over.
+//
+// files starting with "ECO3" are the old fixed layout and get upgraded on load.
+
+pub const SAVE_VERSION: u32 = 1;
+
+const MAGIC: &[u8; 4] = b"ECOS";
+const MAGIC_ECO3: &[u8; 4] = b"ECO3";
+
+// id, p.x, p.y, pp.x, pp.y, e, age, then genome: speed, sense, repro, drive,
+// then u32 parent, u64 born. records from before genomes stop after age and
+// load with the base genome, ones from before lineage have no parent.
+const AGENT_STRIDE: u32 = 56;
+const AGENT_STRIDE_GENOME: u32 = 44;
+const AGENT_STRIDE_MIN: u32 = 28;
+
+// born for records that didn't have it, filled in from the world step on load
+const BORN_UNKNOWN: u64 = u64::MAX;
+
+// everything outside the world needed to continue a run exactly where it was saved
+#[derive(Clone)]
+pub struct RunState {
+    pub seed: u64,
+    pub steps: u64,
+    pub speed: f32,
+    pub rng: SimRng,
+}
+
+// summary for listing saves without loading them
+#[derive(Clone)]
+pub struct SaveMeta {
+    pub time: u64,
+    pub seed: u64,
+    pub steps: u64,
+    // alive per species
+    pub pops: Vec<u32>,
+    pub plants_avg: f32,
+}
+
+pub struct SaveData {
+    pub world: World,
+    pub set: SimSettings,
+    // None for saves made before run state was stored
+    pub run: Option<RunState>,
+    // true when this came from an old ECO3 file
+    pub upgraded: bool,
+}
+
+#[derive(Debug)]
+pub enum SaveError {
+    Io(std::io::Error),
+    BadMagic,
+    NewerVersion(u32),
+    Truncated,
+    MissingSection(&'static str),
+    BadSettings(String),
+    Corrupt(&'static str),
+}
+
+impl fmt::Display for SaveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SaveError::Io(e) => write!(f, "io: {}", e),
+            SaveError::BadMagic => write!(f, "not an ecosim save"),
+            SaveError::NewerVersion(v) => write!(f, "save version {} is newer than {}", v, SAVE_VERSION),
+            SaveError::Truncated => write!(f, "file is truncated"),
+            SaveError::MissingSection(t) => write!(f, "missing {} section", t),
+            SaveError::BadSettings(e) => write!(f, "settings: {}", e),
+            SaveError::Corrupt(what) => write!(f, "corrupt {}", what),
+        }
+    }
+}
+
+impl std::error::Error for SaveError {}
+
+impl From<std::io::Error> for SaveError {
+    fn from(e: std::io::Error) -> Self {
+        SaveError::Io(e)
+    }
+}
+
+pub fn write(path: &str, world: &World, set: &SimSettings, run: &RunState) -> Result<(), SaveError> {
+    let mut out = Vec::new();
+    out.extend_from_slice(MAGIC);
+    put_u32(&mut out, SAVE_VERSION);
+
+    let c = world.counts();
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default();
+    let mut b = Vec::new();
+    put_u64(&mut b, now.as_secs());
+    put_u64(&mut b, run.seed);
+    put_u64(&mut b, run.steps);
+    let pops: Vec<u32> = c.pops.iter().map(|p| p.n).collect();
+    // the fixed pair is what readers from before species lists expect
+    put_u32(&mut b, pops.first().copied().unwrap_or(0));
+    put_u32(&mut b, pops.get(1).copied().unwrap_or(0));
+    put_f32(&mut b, c.plants_avg);
+    put_u32(&mut b, pops.len() as u32);
+    for &n in &pops {
+        put_u32(&mut b, n);
+    }
+    section(&mut out, b"META", &b);
+
+    section(&mut out, b"SETT", set.to_text().as_bytes());
+
+    let mut b = Vec::new();
+    put_i32(&mut b, world.space.w);
+    put_i32(&mut b, world.space.h);
+    put_u32(&mut b, world.next_id);
+    put_u64(&mut b, world.now);
+    section(&mut out, b"WRLD", &b);
+
+    let mut b = Vec::new();
+    put_u32(&mut b, world.plants.len() as u32);
+    b.extend_from_slice(&world.plants);
+    section(&mut out, b"PLNT", &b);
+
+    let mut b = Vec::new();
+    put_u32(&mut b, world.ground.len() as u32);
+    b.extend(world.ground.iter().map(|g| g.to_u8()));
+    section(&mut out, b"TERR", &b);
+
+    let mut b = Vec::new();
+    put_u32(&mut b, world.soil.len() as u32);
+    b.extend_from_slice(&world.soil);
+    section(&mut out, b"SOIL", &b);
+
+    for (kind, list) in world.pops.iter().enumerate() {
+        section(&mut out, b"AGNT", &agents_body(kind as u32, list));
+    }
+
+    let mut b = Vec::new();
+    put_u32(&mut b, world.lineage.len() as u32);
+    for (id, r) in world.lineage.records() {
+        put_u32(&mut b, id);
+        put_u32(&mut b, r.parent);
+        put_u32(&mut b, r.species as u32);
+        put_u64(&mut b, r.born);
+        put_u64(&mut b, r.died.unwrap_or(u64::MAX));
+    }
+    section(&mut out, b"LINE", &b);
+
+    let mut b = Vec::new();
+    put_u64(&mut b, run.seed);
+    put_u64(&mut b, run.steps);
+    put_f32(&mut b, run.speed);
+    b.extend_from_slice(&run.rng.get_seed());
+    put_u64(&mut b, run.rng.get_stream());
+    put_u128(&mut b, run.rng.get_word_pos());
+    section(&mut out, b"RUN ", &b);
+
+    section(&mut out, b"END ", &[]);
+
+    // write next to the old save and swap, so a failed write can't eat it
+    let tmp = format!("{}.tmp", path);
+    std::fs::write(&tmp, &out)?;
+    std::fs::rename(&tmp, path)?;
+    Ok(())
+}
+
+pub fn read(path: &str) -> Result<SaveData, SaveError> {
+    let buf = std::fs::read(path)?;
+    let mut r = Reader { buf: &buf, pos: 0 };
+
+    let magic = r.take(4).map_err(|_| SaveError::BadMagic)?;
+    if magic == MAGIC_ECO3 {
+        return read_eco3(&mut r);
+    }
+    if magic != MAGIC {
+        return Err(SaveError::BadMagic);
+    }
+
+    let version = r.u32()?;
+    if version > SAVE_VERSION {
+        return Err(SaveError::NewerVersion(version));
+    }
+
+    let mut set = None;
+    let mut dims = None;
+    let mut now = None;
+    let mut plants = None;
+    let mut ground = None;
+    let mut soil = None;
+    let mut pops: Vec<(u32, Vec<Agent>)> = Vec::new();
+    let mut lineage = None;
+    let mut run = None;
+
+    loop {
+        let tag: [u8; 4] = r.take(4)?.try_into().unwrap_or_default();
+        let len = r.u32()? as usize;
+        let mut s = Reader { buf: r.take(len)?, pos: 0 };
+
+        match &tag {
+            b"SETT" => {
+                let text = std::str::from_utf8(s.buf).map_err(|_| SaveError::Corrupt("settings"))?;
+                set = Some(SimSettings::from_text_lenient(text).map_err(SaveError::BadSettings)?);
+            }
+            b"WRLD" => {
+                dims = Some((s.i32()?, s.i32()?, s.u32()?));
+                // older saves stop before the world step
+                now = if s.buf.len() - s.pos >= 8 { Some(s.u64()?) } else { None };
+            }
+            b"PLNT" => {
+                let n = s.u32()? as usize;
+                plants = Some(s.take(n)?.to_vec());
+            }
+            b"TERR" => {
+                let n = s.u32()? as usize;
+                let kinds = s.take(n)?.iter().map(|&v| Ground::from_u8(v)).collect::<Option<Vec<_>>>();
+                ground = Some(kinds.ok_or(SaveError::Corrupt("terrain"))?);
+            }
+            b"SOIL" => {
+                let n = s.u32()? as usize;
+                soil = Some(s.take(n)?.to_vec());
+            }
+            b"AGNT" => {
+                let kind = s.u32()?;
+                pops.push((kind, read_agents(&mut s)?));
+            }
+            b"LINE" => {
+                let n = s.u32()?;
+                let mut rows = Vec::with_capacity((n as usize).min(s.buf.len() / 28));
+                for _ in 0..n {
+                    let (id, parent, species, born, died) = (s.u32()?, s.u32()?, s.u32()? as usize, s.u64()?, s.u64()?);
+                    rows.push((id, parent, species, born, (died != u64::MAX).then_some(died)));
+                }
+                lineage = Some(Lineage::restore(rows));
+            }
+            b"RUN " => run = Some(read_run(&mut s)?),
+            b"END " => break,
+            _ => {}
+        }
+    }
+
+    let mut set = set.ok_or(SaveError::MissingSection("SETT"))?;
+    let (w, h, next_id) = dims.ok_or(SaveError::MissingSection("WRLD"))?;
+    let plants = plants.ok_or(SaveError::MissingSection("PLNT"))?;
+
+    let space = checked_space(w, h)?.with_edge(set.boundary);
+    if plants.len() != space.cells() {
+        return Err(SaveError::Corrupt("plant grid"));
+    }
+    // saves from before terrain were flat
+    let ground = ground.unwrap_or_else(|| vec![Ground::Plain; space.cells()]);
+    if ground.len() != space.cells() {
+        return Err(SaveError::Corrupt("terrain"));
+    }
+    let soil = soil.unwrap_or_else(|| vec![set.soil_start; space.cells()]);
+    if soil.len() != space.cells() {
+        return Err(SaveError::Corrupt("soil"));
+    }
+    set.world_w = w;
+    set.world_h = h;
+
+    // kinds past the species list are dropped like unknown sections
+    let mut lists = vec![Vec::new(); set.species.len()];
+    for (kind, list) in pops {
+        if let Some(l) = lists.get_mut(kind as usize) {
+            *l = list;
+        }
+    }
+
+    // saves from before the world step count it as the run's steps
+    let now = now.or(run.as_ref().map(|r| r.steps)).unwrap_or(0);
+    fill_born(&mut lists, now);
+
+    let mut world = World::from_parts(space, plants, ground, species::resolve(&set.species), lists, next_id);
+    world.now = now;
+    world.soil = soil;
+    if let Some(mut l) = lineage {
+        // the living always have records, whatever the file said
+        for (s, list) in world.pops.iter().enumerate() {
+            for a in list {
+                if l.get(a.id).is_none() {
+                    l.born(a.id, a.parent, s, a.born);
+                }
+            }
+        }
+        world.lineage = l;
+    }
+
+    Ok(SaveData { world, set, run, upgraded: false })
+}
+
+pub fn read_meta(path: &str) -> Result<SaveMeta, SaveError> {
+    let buf = std::fs::read(path)?;
+    let mut r = Reader { buf: &buf, pos: 0 };
+
+    if r.take(4).ok() == Some(MAGIC.as_slice()) && r.u32()? <= SAVE_VERSION {
+        loop {
+            let tag = r.take(4)?;
+            let len = r.u32()? as usize;
+            let mut s = Reader { buf: r.take(len)?, pos: 0 };
+            if tag == b"META" {
+                let (time, seed, steps) = (s.u64()?, s.u64()?, s.u64()?);
+                let mut pops = vec![s.u32()?, s.u32()?];
+                let plants_avg = s.f32()?;
+                // the per species list came later, without it it's herb/pred
+                if s.buf.len() - s.pos >= 4 {
+                    let n = s.u32()? as usize;
+                    pops = (0..n).map(|_| s.u32()).collect::<Result<_, _>>()?;
+                }
+                return Ok(SaveMeta { time, seed, steps, pops, plants_avg });
+            }
+            if tag == b"END " {
+                break;
+            }
+        }
+    }
+
+    // no meta section (eco3 or hand made), load the whole thing instead
+    let data = read(path)?;
+    let c = data.world.counts();
+    let time = std::fs::metadata(path)
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    let (seed, steps) = data.run.map(|r| (r.seed, r.steps)).unwrap_or((0, 0));
+
+    let pops = c.pops.iter().map(|p| p.n).collect();
+    Ok(SaveMeta { time, seed, steps, pops, plants_avg: c.plants_avg })
+}
+
+// old fixed layout: "ECO3" i32 w, i32 h, settings, u32 next_id, plants, herbs, preds,
+// then optionally "RUN1" + run state
+fn read_eco3(r: &mut Reader) -> Result<SaveData, SaveError> {
+    let w = r.i32()?;
+    let h = r.i32()?;
+    let space = checked_space(w, h)?;
+
+    // eco3 only knew the herb/pred pair, which is still the default
+    let mut set = SimSettings { world_w: w, world_h: h, ..SimSettings::default() };
+    set.species[0].init = r.u32()?;
+    set.species[1].init = r.u32()?;
+    set.plant_grow = r.u8()?;
+    set.plant_spread = r.f32()?;
+    set.species[0].speed = r.f32()?;
+    set.species[1].speed = r.f32()?;
+    set.species[0].met = r.f32()?;
+    set.species[1].met = r.f32()?;
+    set.eat_radius = r.f32()?;
+    let next_id = r.u32()?;
+
+    let n = r.u32()? as usize;
+    if n != space.cells() {
+        return Err(SaveError::Corrupt("plant grid"));
+    }
+    let plants = r.take(n)?.to_vec();
+
+    let mut herbs = Vec::new();
+    for _ in 0..r.u32()? {
+        herbs.push(read_agent(r)?);
+    }
+
+    let mut preds = Vec::new();
+    for _ in 0..r.u32()? {
+        preds.push(read_agent(r)?);
+    }
+
+    let run = if r.buf.len() - r.pos >= 4 && r.take(4)? == b"RUN1" {
+        Some(read_run(r)?)
+    } else {
+        None
+    };
+
+    let now = run.as_ref().map_or(0, |r| r.steps);
+    let mut lists = vec![herbs, preds];
+    fill_born(&mut lists, now);
+
+    let ground = vec![Ground::Plain; space.cells()];
+    let mut world = World::from_parts(space, plants, ground, species::resolve(&set.species), lists, next_id);
+    world.now = now;
+    // same as a sectioned save without SOIL
+    world.soil = vec![set.soil_start; space.cells()];
+    Ok(SaveData { world, set, run, upgraded: true })
+}
+
+// agents saved without a birth step were born `age` steps ago
+fn fill_born(lists: &mut [Vec<Agent>], now: u64) {
+    for a in lists.iter_mut().flatten() {
+        if a.born == BORN_UNKNOWN {
+            a.born = now.saturating_sub(a.age as u64);
+        }
+    }
+}
+
+fn checked_space(w: i32, h: i32) -> Result<Space, SaveError> {
+    if !(MIN_DIM..=MAX_DIM).contains(&w) || !(MIN_DIM..=MAX_DIM).contains(&h) {
+        return Err(SaveError::Corrupt("world size"));
+    }
+    Ok(Space::new(w, h))
+}
+
+fn agents_body(kind: u32, list: &[Agent]) -> Vec<u8> {
+    let mut b = Vec::with_capacity(12 + list.len() * AGENT_STRIDE as usize);
+    put_u32(&mut b, kind);
+    put_u32(&mut b, list.len() as u32);
+    put_u32(&mut b, AGENT_STRIDE);
+    for a in list {
+        put_u32(&mut b, a.id);
+        put_f32(&mut b, a.p.x);
+        put_f32(&mut b, a.p.y);
+        put_f32(&mut b, a.pp.x);
+        put_f32(&mut b, a.pp.y);
+        put_f32(&mut b, a.e);
+        put_u32(&mut b, a.age);
+        put_f32(&mut b, a.g.speed);
+        put_f32(&mut b, a.g.sense);
+        put_f32(&mut b, a.g.repro);
+        put_f32(&mut b, a.g.drive);
+        put_u32(&mut b, a.parent);
+        put_u64(&mut b, a.born);
+    }
+    b
+}
+
+fn read_agents(r: &mut Reader) -> Result<Vec<Agent>, SaveError> {
+    let n = r.u32()? as usize;
+    let stride = r.u32()? as usize;
+    if stride < AGENT_STRIDE_MIN as usize {
+        return Err(SaveError::Corrupt("agent records"));
+    }
+
+    let mut list = Vec::with_capacity(n.min(r.buf.len() / stride.max(1)));
+    for _ in 0..n {
+        let mut rec = Reader { buf: r.take(stride)?, pos: 0 };
+        let mut a = read_agent(&mut rec)?;
+        if stride >= AGENT_STRIDE_GENOME as usize {
+            a.g = Genome { speed: rec.f32()?, sense: rec.f32()?, repro: rec.f32()?, drive: rec.f32()? };
+            if !(0..TRAIT_COUNT).all(|i| a.g.get(i).is_finite() && a.g.get(i) > 0.0) {
+                return Err(SaveError::Corrupt("agent genome"));
+            }
+        }
+        if stride >= AGENT_STRIDE as usize {
+            a.parent = rec.u32()?;
+            a.born = rec.u64()?;
+        }
+        list.push(a);
+    }
+    Ok(list)
+}
+
+fn read_agent(r: &mut Reader) -> Result<Agent, SaveError> {
+    let id = r.u32()?;
+    let p = vec2(r.f32()?, r.f32()?);
+    let pp = vec2(r.f32()?, r.f32()?);
+    let e = r.f32()?;
+    let age = r.u32()?;
+    Ok(Agent { id, p, pp, e, age, g: Genome::BASE, parent: 0, born: BORN_UNKNOWN })
+}
+
+fn read_run(r: &mut Reader) -> Result<RunState, SaveError> {
+    let seed = r.u64()?;
+    let steps = r.u64()?;
+    let speed = r.f32()?;
+
+    let key: [u8; 32] = r.take(32)?.try_into().unwrap_or_default();
+    let mut rng = SimRng::from_seed(key);
+    rng.set_stream(r.u64()?);
+    rng.set_word_pos(r.u128()?);
+
+    Ok(RunState { seed, steps, speed, rng })
+}
+
+pub(crate) fn section(out: &mut Vec<u8>, tag: &[u8; 4], body: &[u8]) {
+    out.extend_from_slice(tag);
+    put_u32(out, body.len() as u32);
+    out.extend_from_slice(body);
+}
+
+pub(crate) fn put_i32(b: &mut Vec<u8>, v: i32) { b.extend_from_slice(&v.to_le_bytes()) }
+pub(crate) fn put_u32(b: &mut Vec<u8>, v: u32) { b.extend_from_slice(&v.to_le_bytes()) }
+pub(crate) fn put_u64(b: &mut Vec<u8>, v: u64) { b.extend_from_slice(&v.to_le_bytes()) }
+pub(crate) fn put_u128(b: &mut Vec<u8>, v: u128) { b.extend_from_slice(&v.to_le_bytes()) }
+pub(crate) fn put_f32(b: &mut Vec<u8>, v: f32) { b.extend_from_slice(&v.to_le_bytes()) }
+
+pub(crate) struct Reader<'a> {
+    pub(crate) buf: &'a [u8],
+    pub(crate) pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    pub(crate) fn take(&mut self, n: usize) -> Result<&'a [u8], SaveError> {
+        if self.buf.len() - self.pos < n {
+            return Err(SaveError::Truncated);
+        }
+        let s = &self.buf[self.pos..self.pos + n];
+        self.pos += n;
+        Ok(s)
+    }
+
+    pub(crate) fn u8(&mut self) -> Result<u8, SaveError> { Ok(self.take(1)?[0]) }
+    pub(crate) fn i32(&mut self) -> Result<i32, SaveError> { Ok(i32::from_le_bytes(self.take(4)?.try_into().unwrap_or_default())) }
+    pub(crate) fn u32(&mut self) -> Result<u32, SaveError> { Ok(u32::from_le_bytes(self.take(4)?.try_into().unwrap_or_default())) }
+    pub(crate) fn u64(&mut self) -> Result<u64, SaveError> { Ok(u64::from_le_bytes(self.take(8)?.try_into().unwrap_or_default())) }
+    pub(crate) fn u128(&mut self) -> Result<u128, SaveError> { Ok(u128::from_le_bytes(self.take(16)?.try_into().unwrap_or_default())) }
+    pub(crate) fn f32(&mut self) -> Result<f32, SaveError> { Ok(f32::from_le_bytes(self.take(4)?.try_into().unwrap_or_default())) }
+}
@@ -0,0 +1,66 @@
+// seasons and day / night. both are worked out from the world step, so
+// saves, rewinds and replays get them right without storing anything.
+//
+// a year is `season_len` steps. plant growth and spreading are scaled by
+// 1 + season_amp * sin over the year, or stepped through the season_grow
+// table when it has entries. a day is `day_len` steps starting at dawn,
+// light goes 0..1 with it and each species' night_sight / night_speed
+// blend in as it gets dark. either length at 0 turns that cycle off.
+
+use std::f32::consts::TAU;
+
+use crate::config::SimSettings;
+
+pub const SEASON_NAMES: [&str; 4] = ["spring", "summer", "autumn", "winter"];
+
+fn year_phase(set: &SimSettings, step: u64) -> Option<f32> {
+    (set.season_len > 0).then(|| (step % set.season_len as u64) as f32 / set.season_len as f32)
+}
+
+// plant growth and spread multiplier at this step
+pub fn growth(set: &SimSettings, step: u64) -> f32 {
+    match year_phase(set, step) {
+        None => 1.0,
+        Some(p) if !set.season_grow.is_empty() => set.season_grow[season_at(set, p)],
+        Some(p) => (1.0 + set.season_amp * (TAU * p).sin()).max(0.0),
+    }
+}
+
+// spring, summer, autumn, winter round the sine year, centred on its
+// middle, top, middle and bottom. a table year has one season per entry
+fn season_at(set: &SimSettings, p: f32) -> usize {
+    match set.season_grow.len() {
+        0 => ((p + 0.125) * 4.0) as usize % 4,
+        n => ((p * n as f32) as usize).min(n - 1),
+    }
+}
+
+// which season it is, None with seasons off
+pub fn season(set: &SimSettings, step: u64) -> Option<usize> {
+    year_phase(set, step).map(|p| season_at(set, p))
+}
+
+pub fn season_name(set: &SimSettings, season: usize) -> String {
+    match set.season_grow.len() {
+        0 | 4 => SEASON_NAMES[season % 4].to_string(),
+        _ => format!("season {}", season + 1),
+    }
+}
+
+// 1 at noon, 0 at midnight, 0.5 at dawn and dusk. always 1 with days off
+pub fn light(set: &SimSettings, step: u64) -> f32 {
+    if set.day_len == 0 {
+        return 1.0;
+    }
+    let p = (step % set.day_len as u64) as f32 / set.day_len as f32;
+    0.5 + 0.5 * (TAU * p).sin()
+}
+
+pub fn is_night(light: f32) -> bool {
+    light < 0.5
+}
+
+// a night multiplier blended in by how dark it is, exactly 1 in full light
+pub fn at_night(mult: f32, light: f32) -> f32 {
+    if light >= 1.0 { 1.0 } else { mult + (1.0 - mult) * light }
+}
@@ -0,0 +1,47 @@
+// soil nutrients, one u8 per cell, only used with `soil = true`.
+//
+// plants grow in proportion to what the soil holds, MID being the plain
+// rate. grazing takes soil_graze nutrients per unit of plant eaten, and
+// whoever starves or gets eaten gives soil_corpse back to the cell they
+// died in. every soil_drift steps each cell moves one unit back towards
+// soil_start, so bare patches recover and hotspots fade, slowly.
+
+use crate::vec2::Vec2;
+use crate::world::Space;
+
+// soil level that grows plants at the plain rate
+pub const MID: u8 = 128;
+
+// growth of one cell scaled by its nutrients, 0 on empty soil
+pub fn growth(g: i32, soil: u8) -> i32 {
+    g * soil as i32 / MID as i32
+}
+
+// nutrients taken out by eating `take` plant
+pub fn drain(take: u8, per: f32) -> u8 {
+    (take as f32 * per).round().min(255.0) as u8
+}
+
+// a death gives its nutrients back where it happened
+pub fn deposit(soil: &mut [u8], sp: Space, p: Vec2, amount: u8) {
+    let i = sp.cell_of(p);
+    soil[i] = soil[i].saturating_add(amount);
+}
+
+// one unit back towards `to`
+pub fn drift(soil: &mut [u8], to: u8) {
+    for v in soil {
+        if *v < to {
+            *v += 1;
+        } else if *v > to {
+            *v -= 1;
+        }
+    }
+}
+
+pub fn average(soil: &[u8]) -> f32 {
+    if soil.is_empty() {
+        return 0.0;
+    }
+    soil.iter().map(|&v| v as u64).sum::<u64>() as f32 / soil.len() as f32 / 255.0
+}
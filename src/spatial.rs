@@ -0,0 +1,131 @@
+use crate::vec2::Vec2;
+use crate::world::Space;
+
+// target bucket size in world units
+const BUCKET: f32 = 4.0;
+
+// uniform bucket grid over the world, rebuilt from a position list each step.
+// stores indices into that list, so callers keep their own Vec order.
+#[derive(Default)]
+pub struct SpatialGrid {
+    gw: i32,
+    gh: i32,
+    cw: f32,
+    ch: f32,
+    // false when the world has walls, buckets past them are empty
+    wraps: bool,
+    starts: Vec<u32>,
+    items: Vec<u32>,
+}
+
+impl SpatialGrid {
+    pub fn rebuild(&mut self, sp: Space, pts: &[Vec2]) {
+        // bucket sizes divide the world evenly so wrapped neighbours line up
+        self.gw = ((sp.w as f32 / BUCKET) as i32).max(1);
+        self.gh = ((sp.h as f32 / BUCKET) as i32).max(1);
+        self.cw = sp.w as f32 / self.gw as f32;
+        self.ch = sp.h as f32 / self.gh as f32;
+        self.wraps = sp.wraps();
+
+        let n = (self.gw * self.gh) as usize;
+        self.starts.clear();
+        self.starts.resize(n + 1, 0);
+
+        for &p in pts {
+            let b = self.bucket_of(p);
+            self.starts[b + 1] += 1;
+        }
+        for i in 0..n {
+            self.starts[i + 1] += self.starts[i];
+        }
+
+        self.items.clear();
+        self.items.resize(pts.len(), 0);
+        let mut fill = self.starts[..n].to_vec();
+        for (i, &p) in pts.iter().enumerate() {
+            let b = self.bucket_of(p);
+            self.items[fill[b] as usize] = i as u32;
+            fill[b] += 1;
+        }
+    }
+
+    fn bucket_xy(&self, p: Vec2) -> (i32, i32) {
+        let bx = ((p.x / self.cw) as i32).clamp(0, self.gw - 1);
+        let by = ((p.y / self.ch) as i32).clamp(0, self.gh - 1);
+        (bx, by)
+    }
+
+    fn bucket_of(&self, p: Vec2) -> usize {
+        let (bx, by) = self.bucket_xy(p);
+        (by * self.gw + bx) as usize
+    }
+
+    fn bucket(&self, bx: i32, by: i32) -> &[u32] {
+        if !self.wraps && (bx < 0 || by < 0 || bx >= self.gw || by >= self.gh) {
+            return &[];
+        }
+        let x = bx.rem_euclid(self.gw);
+        let y = by.rem_euclid(self.gh);
+        let b = (y * self.gw + x) as usize;
+        &self.items[self.starts[b] as usize..self.starts[b + 1] as usize]
+    }
+
+    // nearest point strictly closer than r for which `keep` holds.
+    // ties go to the lowest index, same as a front-to-back linear scan.
+    pub fn nearest(
+        &self,
+        sp: Space,
+        pts: &[Vec2],
+        p: Vec2,
+        r: f32,
+        keep: impl Fn(usize) -> bool,
+    ) -> Option<(usize, f32)> {
+        if pts.is_empty() {
+            return None;
+        }
+
+        let (cx, cy) = self.bucket_xy(p);
+        let cmin = self.cw.min(self.ch);
+
+        // +1 because p can sit anywhere inside its own bucket
+        let reach = (r / cmin) as i32 + 1;
+        // half way round a torus reaches everything, walls need the lot
+        let span = if self.wraps { self.gw.max(self.gh) / 2 + 1 } else { self.gw.max(self.gh) };
+        let max_ring = reach.min(span);
+
+        let mut best: Option<(usize, f32)> = None;
+
+        for ring in 0..=max_ring {
+            for by in cy - ring..=cy + ring {
+                let edge_row = by == cy - ring || by == cy + ring;
+                let step = if edge_row || ring == 0 { 1 } else { 2 * ring as usize };
+
+                for bx in (cx - ring..=cx + ring).step_by(step) {
+                    for &i in self.bucket(bx, by) {
+                        let i = i as usize;
+                        let d = sp.dist(p, pts[i]);
+                        if d >= r || !keep(i) {
+                            continue;
+                        }
+                        let better = match best {
+                            None => true,
+                            Some((bi, bd)) => d < bd || (d == bd && i < bi),
+                        };
+                        if better {
+                            best = Some((i, d));
+                        }
+                    }
+                }
+            }
+
+            // anything in the next ring is at least ring * cmin away
+            if let Some((_, bd)) = best
+                && bd < ring as f32 * cmin
+            {
+                break;
+            }
+        }
+
+        best
+    }
+}
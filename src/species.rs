@@ -0,0 +1,257 @@
+// species definitions. the world runs whatever food web these describe,
+// the defaults are the classic herbivore + predator pair.
+
+use crate::config::check_range;
+
+pub const MAX_SPECIES: usize = 16;
+
+// what the diet list uses for grazing
+pub const PLANTS: &str = "plants";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Shape {
+    Circle,
+    Triangle,
+    Square,
+    Diamond,
+}
+
+impl Shape {
+    pub fn name(self) -> &'static str {
+        match self {
+            Shape::Circle => "circle",
+            Shape::Triangle => "triangle",
+            Shape::Square => "square",
+            Shape::Diamond => "diamond",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Shape> {
+        match s {
+            "circle" => Some(Shape::Circle),
+            "triangle" => Some(Shape::Triangle),
+            "square" => Some(Shape::Square),
+            "diamond" => Some(Shape::Diamond),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct SpeciesDef {
+    pub name: String,
+    // "plants" and/or names of other species
+    pub eats: Vec<String>,
+    pub init: u32,
+    // starting energy is init_e + up to init_e_var
+    pub init_e: f32,
+    pub init_e_var: f32,
+    pub speed: f32,
+    pub met: f32,
+    // random wobble added to the heading every step
+    pub wander: f32,
+    pub flee_radius: f32,
+    pub flee_weight: f32,
+    pub chase_radius: f32,
+    // plant units taken per step and energy per unit
+    pub bite: u8,
+    pub graze_e: f32,
+    // energy per prey caught
+    pub kill_e: f32,
+    // breeds above repro_e with chance repro_p per step, keeping
+    // repro_keep of its energy; the kid starts with child_e
+    pub repro_e: f32,
+    pub repro_p: f32,
+    pub repro_keep: f32,
+    pub child_e: f32,
+    pub child_spread: f32,
+    // below respawn_below alive, respawn_n fresh ones drop in with
+    // chance respawn_p per step
+    pub respawn_below: u32,
+    pub respawn_p: f32,
+    pub respawn_n: u32,
+    pub respawn_e: f32,
+    // sense radius and speed in the dark, as a share of the daytime ones.
+    // only matters with day_len set
+    pub night_sight: f32,
+    pub night_speed: f32,
+    pub color: [u8; 3],
+    pub shape: Shape,
+}
+
+impl SpeciesDef {
+    // also the starting point for any new species in a settings file
+    pub fn herb() -> Self {
+        Self {
+            name: "herb".to_string(),
+            eats: vec![PLANTS.to_string()],
+            init: 900,
+            init_e: 1.2,
+            init_e_var: 0.6,
+            speed: 0.22,
+            met: 0.014,
+            wander: 0.35,
+            flee_radius: 8.0,
+            flee_weight: 1.15,
+            chase_radius: 18.0,
+            bite: 16,
+            graze_e: 0.0022,
+            kill_e: 0.85,
+            repro_e: 2.2,
+            repro_p: 0.10,
+            repro_keep: 0.62,
+            child_e: 1.0,
+            child_spread: 0.9,
+            respawn_below: 20,
+            respawn_p: 0.25,
+            respawn_n: 18,
+            respawn_e: 1.4,
+            // grazers bed down at night
+            night_sight: 1.0,
+            night_speed: 0.5,
+            color: [250, 217, 38],
+            shape: Shape::Circle,
+        }
+    }
+
+    pub fn pred() -> Self {
+        Self {
+            name: "pred".to_string(),
+            eats: vec!["herb".to_string()],
+            init: 40,
+            init_e: 1.6,
+            init_e_var: 0.8,
+            speed: 0.32,
+            met: 0.020,
+            wander: 0.22,
+            repro_e: 2.7,
+            repro_p: 0.08,
+            repro_keep: 0.64,
+            child_e: 1.2,
+            child_spread: 0.8,
+            respawn_below: 6,
+            respawn_p: 0.20,
+            respawn_n: 5,
+            respawn_e: 1.8,
+            // and hunters can't see as far
+            night_sight: 0.6,
+            night_speed: 1.0,
+            color: [242, 64, 51],
+            shape: Shape::Triangle,
+            ..Self::herb()
+        }
+    }
+
+    pub fn named(name: &str) -> Self {
+        Self { name: name.to_string(), ..Self::herb() }
+    }
+}
+
+pub fn default_species() -> Vec<SpeciesDef> {
+    vec![SpeciesDef::herb(), SpeciesDef::pred()]
+}
+
+// a species with its diet resolved to indices
+#[derive(Clone, Debug, PartialEq)]
+pub struct Species {
+    pub def: SpeciesDef,
+    pub eats_plants: bool,
+    pub prey: Vec<usize>,
+    // species that eat this one
+    pub threats: Vec<usize>,
+}
+
+pub fn check(defs: &[SpeciesDef]) -> Result<(), String> {
+    if defs.is_empty() {
+        return Err("need at least one species".to_string());
+    }
+    if defs.len() > MAX_SPECIES {
+        return Err(format!("at most {} species, got {}", MAX_SPECIES, defs.len()));
+    }
+    for (i, d) in defs.iter().enumerate() {
+        if d.name.is_empty() || !d.name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+            return Err(format!("bad species name `{}`", d.name));
+        }
+        if d.name == PLANTS {
+            return Err(format!("species name `{}` is reserved for plants in `eats`", PLANTS));
+        }
+        if defs[..i].iter().any(|o| o.name == d.name) {
+            return Err(format!("species name `{}` used twice", d.name));
+        }
+        let n = &d.name;
+        for (k, v, lo, hi) in [
+            ("init_e", d.init_e, 0.0, f32::MAX),
+            ("init_e_var", d.init_e_var, 0.0, f32::MAX),
+            ("speed", d.speed, 0.0, 4.0),
+            ("met", d.met, 0.0, f32::MAX),
+            ("wander", d.wander, 0.0, f32::MAX),
+            ("flee_radius", d.flee_radius, 0.0, 256.0),
+            ("chase_radius", d.chase_radius, 0.0, 256.0),
+            ("bite", d.bite as f32, 0.0, 255.0),
+            ("graze_e", d.graze_e, 0.0, f32::MAX),
+            ("kill_e", d.kill_e, 0.0, f32::MAX),
+            ("repro_e", d.repro_e, 0.0, f32::MAX),
+            ("repro_p", d.repro_p, 0.0, 1.0),
+            ("repro_keep", d.repro_keep, 0.0, 1.0),
+            ("child_e", d.child_e, 0.0, f32::MAX),
+            ("child_spread", d.child_spread, 0.0, 256.0),
+            ("respawn_e", d.respawn_e, 0.0, f32::MAX),
+            ("respawn_p", d.respawn_p, 0.0, 1.0),
+            ("night_sight", d.night_sight, 0.0, 4.0),
+            ("night_speed", d.night_speed, 0.0, 4.0),
+        ] {
+            check_range(&format!("{}.{}", n, k), v, lo, hi)?;
+        }
+        for e in &d.eats {
+            if e == &d.name {
+                return Err(format!("species `{}` can't eat itself", d.name));
+            }
+            if e != PLANTS && !defs.iter().any(|o| &o.name == e) {
+                return Err(format!("species `{}` eats unknown `{}`", d.name, e));
+            }
+        }
+    }
+    Ok(())
+}
+
+// unknown names in a diet are dropped, check() is what reports them
+pub fn resolve(defs: &[SpeciesDef]) -> Vec<Species> {
+    let idx = |n: &str| defs.iter().position(|d| d.name == n);
+
+    let mut out: Vec<Species> = defs
+        .iter()
+        .map(|d| Species {
+            def: d.clone(),
+            eats_plants: d.eats.iter().any(|e| e == PLANTS),
+            prey: d.eats.iter().filter_map(|e| idx(e)).collect(),
+            threats: Vec::new(),
+        })
+        .collect();
+
+    for s in 0..out.len() {
+        for p in out[s].prey.clone() {
+            out[p].threats.push(s);
+        }
+    }
+    out
+}
+
+// hunters move before what they hunt: sorted by trophic level (longest
+// prey chain below it), highest first, ties in definition order
+pub fn step_order(species: &[Species]) -> Vec<usize> {
+    let n = species.len();
+    let mut level = vec![0usize; n];
+    // relax n times, cycles just stop growing once they hit n
+    for _ in 0..n {
+        for s in 0..n {
+            for &p in &species[s].prey {
+                if p != s {
+                    level[s] = level[s].max((level[p] + 1).min(n));
+                }
+            }
+        }
+    }
+    let mut order: Vec<usize> = (0..n).collect();
+    order.sort_by_key(|&s| std::cmp::Reverse(level[s]));
+    order
+}
@@ -0,0 +1,247 @@
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+
+use crate::genome::{TRAIT_COUNT, TRAIT_NAMES};
+
+// one entry per species, same order as the world's species list
+#[derive(Clone, Debug, PartialEq)]
+pub struct Counts {
+    pub plants_avg: f32,
+    pub pops: Vec<PopCounts>,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct PopCounts {
+    pub n: u32,
+    // mean energy, 0 when extinct
+    pub e_avg: f32,
+    pub traits: TraitStats,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Deltas {
+    // per species, births include respawns, deaths include being eaten
+    pub births: Vec<u32>,
+    pub deaths: Vec<u32>,
+}
+
+// mean and spread of one genome trait over a population, 0 when extinct
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct TraitStat {
+    pub mean: f32,
+    pub sd: f32,
+}
+
+// indexed like genome::TRAIT_NAMES
+pub type TraitStats = [TraitStat; TRAIT_COUNT];
+
+#[derive(Default)]
+pub struct PopHistory {
+    pub n: VecDeque<u32>,
+    pub births: VecDeque<u32>,
+    pub deaths: VecDeque<u32>,
+    pub e: VecDeque<f32>,
+    pub traits: VecDeque<TraitStats>,
+}
+
+pub struct StatsHistory {
+    cap: usize,
+    pub steps: VecDeque<u64>,
+    pub plants: VecDeque<f32>,
+    pub pops: Vec<PopHistory>,
+}
+
+impl Default for StatsHistory {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl StatsHistory {
+    pub fn new() -> Self {
+        Self {
+            cap: 520,
+            steps: VecDeque::new(),
+            plants: VecDeque::new(),
+            pops: Vec::new(),
+        }
+    }
+
+    pub fn push(&mut self, s: u64, c: &Counts, d: &Deltas) {
+        // a species showing up mid history gets zeros for the steps it missed
+        while self.pops.len() < c.pops.len() {
+            let mut h = PopHistory::default();
+            for _ in 0..self.steps.len() {
+                h.n.push_back(0);
+                h.births.push_back(0);
+                h.deaths.push_back(0);
+                h.e.push_back(0.0);
+                h.traits.push_back(TraitStats::default());
+            }
+            self.pops.push(h);
+        }
+
+        self.steps.push_back(s);
+        self.plants.push_back(c.plants_avg);
+        for (i, h) in self.pops.iter_mut().enumerate() {
+            let pc = c.pops.get(i).copied().unwrap_or_default();
+            h.n.push_back(pc.n);
+            h.births.push_back(d.births.get(i).copied().unwrap_or(0));
+            h.deaths.push_back(d.deaths.get(i).copied().unwrap_or(0));
+            h.e.push_back(pc.e_avg);
+            h.traits.push_back(pc.traits);
+        }
+
+        while self.steps.len() > self.cap {
+            self.steps.pop_front();
+            self.plants.pop_front();
+            for h in self.pops.iter_mut() {
+                h.n.pop_front();
+                h.births.pop_front();
+                h.deaths.pop_front();
+                h.e.pop_front();
+                h.traits.pop_front();
+            }
+        }
+    }
+
+    // drops everything recorded after step s, for rewinding
+    pub fn truncate_after(&mut self, s: u64) {
+        while self.steps.back().is_some_and(|&b| b > s) {
+            self.steps.pop_back();
+            self.plants.pop_back();
+            for h in self.pops.iter_mut() {
+                h.n.pop_back();
+                h.births.pop_back();
+                h.deaths.pop_back();
+                h.e.pop_back();
+                h.traits.pop_back();
+            }
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.steps.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.steps.is_empty()
+    }
+
+    pub fn max_agents_recent(&self) -> u32 {
+        let mut m = 1u32;
+        for h in &self.pops {
+            for &v in h.n.iter() {
+                if v > m {
+                    m = v;
+                }
+            }
+        }
+        m
+    }
+
+    pub fn max_flow_recent(&self) -> u32 {
+        let mut m = 1u32;
+        for h in &self.pops {
+            for &v in h.births.iter().chain(h.deaths.iter()) {
+                if v > m {
+                    m = v;
+                }
+            }
+        }
+        m
+    }
+
+    pub fn max_energy_recent(&self) -> f32 {
+        let mut m = 0.1f32;
+        for h in &self.pops {
+            for &v in h.e.iter() {
+                if v > m {
+                    m = v;
+                }
+            }
+        }
+        m
+    }
+}
+
+// csv recorder, one row per sim step. columns are grouped by stat, one
+// column per species inside each group (shown for the default herb/pred):
+//   step        step number, first row is 1
+//   plants_avg  mean plant density over all cells, 0..1
+//   herbs       herbivores alive after the step
+//   preds       predators alive after the step
+//   herb_birth  herbivores born this step (incl. respawns)
+//   herb_death  herbivores starved or eaten this step
+//   pred_birth  predators born this step (incl. respawns)
+//   pred_death  predators starved or eaten this step
+//   herb_e_avg  mean herbivore energy (0 when extinct)
+//   pred_e_avg  mean predator energy (0 when extinct)
+//   herb_speed, herb_sense, herb_repro, herb_drive
+//               mean herbivore genome traits, 1.0 = baseline (0 when extinct)
+//   pred_speed, pred_sense, pred_repro, pred_drive
+//               same for predators
+// with the same species the column order never changes, new stats only
+// ever get appended at the end.
+pub fn csv_header(names: &[String]) -> String {
+    let mut cols = vec!["step".to_string(), "plants_avg".to_string()];
+    cols.extend(names.iter().map(|n| format!("{}s", n)));
+    for n in names {
+        cols.push(format!("{}_birth", n));
+        cols.push(format!("{}_death", n));
+    }
+    cols.extend(names.iter().map(|n| format!("{}_e_avg", n)));
+    for n in names {
+        cols.extend(TRAIT_NAMES.iter().map(|t| format!("{}_{}", n, t)));
+    }
+    cols.join(",")
+}
+
+pub struct CsvRecorder {
+    out: BufWriter<File>,
+    path: String,
+    rows: u64,
+}
+
+impl CsvRecorder {
+    pub fn create(path: &str, names: &[String]) -> std::io::Result<Self> {
+        let mut out = BufWriter::new(File::create(path)?);
+        writeln!(out, "{}", csv_header(names))?;
+        Ok(Self { out, path: path.to_string(), rows: 0 })
+    }
+
+    pub fn push(&mut self, step: u64, c: &Counts, d: &Deltas) -> std::io::Result<()> {
+        write!(self.out, "{},{}", step, c.plants_avg)?;
+        for p in &c.pops {
+            write!(self.out, ",{}", p.n)?;
+        }
+        for (b, dd) in d.births.iter().zip(&d.deaths) {
+            write!(self.out, ",{},{}", b, dd)?;
+        }
+        for p in &c.pops {
+            write!(self.out, ",{}", p.e_avg)?;
+        }
+        for p in &c.pops {
+            for t in &p.traits {
+                write!(self.out, ",{}", t.mean)?;
+            }
+        }
+        writeln!(self.out)?;
+        self.rows += 1;
+        Ok(())
+    }
+
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    pub fn rows(&self) -> u64 {
+        self.rows
+    }
+
+    pub fn finish(mut self) -> std::io::Result<u64> {
+        self.out.flush()?;
+        Ok(self.rows)
+    }
+}
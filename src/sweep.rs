@@ -0,0 +1,210 @@
+// parameter sweeps: every combination of a few settings, a handful of
+// seeds each, every run boiled down to a few numbers per species.
+
+use crate::config::{SimSettings, SimTuning};
+use crate::util::rng_from_seed;
+use crate::world::World;
+
+// one swept setting and the values it takes
+#[derive(Clone, Debug, PartialEq)]
+pub struct Axis {
+    pub key: String,
+    pub values: Vec<String>,
+}
+
+impl Axis {
+    // `key=from:to:n` for n evenly spaced values, or `key=a,b,c`
+    pub fn parse(s: &str) -> Result<Axis, String> {
+        let (key, spec) = s.split_once('=').ok_or(format!("bad sweep `{}`, expected key=from:to:n or key=a,b,c", s))?;
+        let key = key.trim().to_string();
+        let spec = spec.trim();
+
+        let values: Vec<String> = if spec.contains(':') {
+            let parts: Vec<&str> = spec.split(':').collect();
+            let bad = || format!("bad range `{}`, expected from:to:n", spec);
+            if parts.len() != 3 {
+                return Err(bad());
+            }
+            let a: f64 = parts[0].trim().parse().map_err(|_| bad())?;
+            let b: f64 = parts[1].trim().parse().map_err(|_| bad())?;
+            let n: usize = parts[2].trim().parse().map_err(|_| bad())?;
+            if n == 0 {
+                return Err(bad());
+            }
+            (0..n)
+                .map(|i| {
+                    let t = if n == 1 { 0.0 } else { i as f64 / (n - 1) as f64 };
+                    // rounded so 0.1 steps print as 0.3 and not 0.30000000000000004,
+                    // whole numbers print without a dot so integer keys parse
+                    let v = ((a + (b - a) * t) * 1e9).round() / 1e9;
+                    v.to_string()
+                })
+                .collect()
+        } else {
+            spec.split(',').map(|v| v.trim().to_string()).filter(|v| !v.is_empty()).collect()
+        };
+
+        if values.is_empty() {
+            return Err(format!("sweep `{}` has no values", key));
+        }
+        Ok(Axis { key, values })
+    }
+}
+
+// one grid point: the swept values in axis order and the settings they make
+pub struct Point {
+    pub values: Vec<String>,
+    pub set: SimSettings,
+}
+
+// every combination, the last axis varying fastest
+pub fn points(base: &SimSettings, axes: &[Axis]) -> Result<Vec<Point>, String> {
+    let mut out = vec![Point { values: Vec::new(), set: base.clone() }];
+    for ax in axes {
+        let mut next = Vec::with_capacity(out.len() * ax.values.len());
+        for p in &out {
+            for v in &ax.values {
+                let mut set = p.set.clone();
+                set.set_value(&ax.key, v)?;
+                set.validate().map_err(|e| format!("{}={}: {}", ax.key, v, e))?;
+                let mut values = p.values.clone();
+                values.push(v.clone());
+                next.push(Point { values, set });
+            }
+        }
+        out = next;
+    }
+    Ok(out)
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct PopSummary {
+    // first step the count hit 0. respawns can bring it back afterwards
+    pub extinct_at: Option<u64>,
+    // population over the steps after burn-in
+    pub mean: f64,
+    pub var: f64,
+    // steps between population peaks, None if it doesn't cycle
+    pub period: Option<f64>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct RunSummary {
+    pub seed: u64,
+    pub pops: Vec<PopSummary>,
+}
+
+// runs one world for `steps`, stats skip the first `burn` steps.
+// always single threaded, sweeps spread whole runs over threads instead
+pub fn run_one(set: &SimSettings, seed: u64, steps: u64, burn: u64) -> RunSummary {
+    let mut set = set.clone();
+    set.threads = 1;
+    let dt = SimTuning::default().fixed_dt;
+
+    let mut rng = rng_from_seed(seed);
+    let mut world = World::new(&mut rng, &set);
+    let ns = world.species().len();
+
+    let mut series: Vec<Vec<f64>> = vec![Vec::new(); ns];
+    let mut extinct_at: Vec<Option<u64>> = vec![None; ns];
+
+    for s in 1..=steps {
+        world.step(&mut rng, &set, dt);
+        for (k, p) in world.counts().pops.iter().enumerate() {
+            if p.n == 0 && extinct_at[k].is_none() {
+                extinct_at[k] = Some(s);
+            }
+            if s > burn {
+                series[k].push(p.n as f64);
+            }
+        }
+    }
+
+    let pops = series
+        .iter()
+        .zip(extinct_at)
+        .map(|(xs, extinct_at)| {
+            let (mean, var) = mean_var(xs);
+            PopSummary { extinct_at, mean, var, period: period(xs) }
+        })
+        .collect();
+    RunSummary { seed, pops }
+}
+
+pub fn mean_var(xs: &[f64]) -> (f64, f64) {
+    if xs.is_empty() {
+        return (0.0, 0.0);
+    }
+    let n = xs.len() as f64;
+    let mean = xs.iter().sum::<f64>() / n;
+    let var = xs.iter().map(|x| (x - mean) * (x - mean)).sum::<f64>() / n;
+    (mean, var)
+}
+
+// mean spacing of upward crossings through the mean. a crossing only
+// counts once the series has gone a quarter sd below the mean and then a
+// quarter sd above, so noise around the mean doesn't look like cycles.
+// needs two full cycles
+pub fn period(xs: &[f64]) -> Option<f64> {
+    let (mean, var) = mean_var(xs);
+    let band = var.sqrt() * 0.25;
+    if band < 1e-9 {
+        return None;
+    }
+
+    let mut low = false;
+    let mut ups = Vec::new();
+    for (i, &x) in xs.iter().enumerate() {
+        if x < mean - band {
+            low = true;
+        } else if low && x > mean + band {
+            low = false;
+            ups.push(i);
+        }
+    }
+
+    if ups.len() < 3 {
+        return None;
+    }
+    Some((ups[ups.len() - 1] - ups[0]) as f64 / (ups.len() - 1) as f64)
+}
+
+// one species over all the seeds of a grid point
+#[derive(Clone, Debug, PartialEq)]
+pub struct PopAggregate {
+    // share of runs where it hit 0 at some point
+    pub extinct_frac: f64,
+    // mean first-extinction step over the runs that had one
+    pub extinct_step: Option<f64>,
+    // pooled over every post burn-in step of every run
+    pub mean: f64,
+    pub var: f64,
+    // mean over the runs that cycled
+    pub period: Option<f64>,
+}
+
+pub fn aggregate(runs: &[RunSummary]) -> Vec<PopAggregate> {
+    let ns = runs.first().map_or(0, |r| r.pops.len());
+    let n = runs.len() as f64;
+
+    (0..ns)
+        .map(|k| {
+            let pops: Vec<&PopSummary> = runs.iter().map(|r| &r.pops[k]).collect();
+            let ext: Vec<f64> = pops.iter().filter_map(|p| p.extinct_at).map(|s| s as f64).collect();
+            let per: Vec<f64> = pops.iter().filter_map(|p| p.period).collect();
+
+            // law of total variance, every run has the same number of steps
+            let means: Vec<f64> = pops.iter().map(|p| p.mean).collect();
+            let (mean, var_of_means) = mean_var(&means);
+            let mean_of_vars = pops.iter().map(|p| p.var).sum::<f64>() / n;
+
+            PopAggregate {
+                extinct_frac: ext.len() as f64 / n,
+                extinct_step: (!ext.is_empty()).then(|| mean_var(&ext).0),
+                mean,
+                var: mean_of_vars + var_of_means,
+                period: (!per.is_empty()).then(|| mean_var(&per).0),
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,306 @@
+// the ground under the plants. every cell has a kind that stays put for the
+// life of the world: rock can't be walked through, water slows agents down,
+// and the kind decides how fast plants grow there. a flat world is all
+// plain and steps exactly like worlds did before there was terrain.
+
+use std::sync::Arc;
+
+use rand09::Rng;
+
+use crate::util::keyed_rng;
+use crate::vec2::{Vec2, vec2};
+use crate::world::Space;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum Ground {
+    #[default]
+    Plain,
+    Fertile,
+    Barren,
+    Water,
+    Rock,
+}
+
+pub const GROUNDS: [Ground; 5] = [Ground::Plain, Ground::Fertile, Ground::Barren, Ground::Water, Ground::Rock];
+
+// speed through water, as a share of speed on land
+const WATER_SPEED: f32 = 0.4;
+
+impl Ground {
+    pub fn from_u8(v: u8) -> Option<Self> {
+        GROUNDS.get(v as usize).copied()
+    }
+
+    pub fn to_u8(self) -> u8 {
+        self as u8
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Ground::Plain => "plain",
+            Ground::Fertile => "fertile",
+            Ground::Barren => "barren",
+            Ground::Water => "water",
+            Ground::Rock => "rock",
+        }
+    }
+
+    pub fn blocks(self) -> bool {
+        self == Ground::Rock
+    }
+
+    // plants never grow on water or rock, and can't be painted there
+    pub fn grows(self) -> bool {
+        !matches!(self, Ground::Water | Ground::Rock)
+    }
+
+    pub fn speed(self) -> f32 {
+        if self == Ground::Water { WATER_SPEED } else { 1.0 }
+    }
+
+    // plant density added per step, from the plain rate and its jitter
+    pub fn growth(self, grow: i32, jitter: i32) -> i32 {
+        match self {
+            Ground::Plain => grow + jitter,
+            Ground::Fertile => grow * 2 + jitter,
+            Ground::Barren => (grow + jitter) / 3,
+            Ground::Water | Ground::Rock => 0,
+        }
+    }
+}
+
+// where a world's terrain comes from, the `terrain` setting
+#[derive(Clone, Debug, PartialEq, Default)]
+pub enum TerrainSource {
+    #[default]
+    Flat,
+    // made up from the seed
+    Random,
+    // read when the settings are, so a missing file fails early
+    Image { path: String, map: Arc<GroundMap> },
+}
+
+impl TerrainSource {
+    // "flat", "random", or a path to a .ppm / .pgm image
+    pub fn parse(s: &str) -> Result<Self, String> {
+        match s {
+            "flat" | "" => Ok(TerrainSource::Flat),
+            "random" => Ok(TerrainSource::Random),
+            path => {
+                let map = GroundMap::read(path)?;
+                Ok(TerrainSource::Image { path: path.to_string(), map: Arc::new(map) })
+            }
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        match self {
+            TerrainSource::Flat => "flat",
+            TerrainSource::Random => "random",
+            TerrainSource::Image { path, .. } => path,
+        }
+    }
+
+    // the ground for a new world. only random terrain draws from the rng,
+    // and only once, so flat worlds come out the same as they always have
+    pub fn build(&self, sp: Space, rng: &mut impl Rng) -> Vec<Ground> {
+        match self {
+            TerrainSource::Flat => vec![Ground::Plain; sp.cells()],
+            TerrainSource::Random => generate(sp, rng.random::<u64>()),
+            TerrainSource::Image { map, .. } => map.fit(sp),
+        }
+    }
+}
+
+// a terrain picture at its own size, stretched over the world when used
+#[derive(Clone, Debug, PartialEq)]
+pub struct GroundMap {
+    pub w: usize,
+    pub h: usize,
+    pub cells: Vec<Ground>,
+}
+
+impl GroundMap {
+    pub fn read(path: &str) -> Result<Self, String> {
+        let buf = std::fs::read(path).map_err(|e| format!("{}: {}", path, e))?;
+        Self::from_pnm(&buf).map_err(|e| format!("{}: {}", path, e))
+    }
+
+    // netpbm, which any image editor can export: P2 / P5 grey or P3 / P6
+    // colour. dark is rock, blue is water, green is fertile, yellow and
+    // brown are barren, anything else (white, grey) is plain
+    pub fn from_pnm(buf: &[u8]) -> Result<Self, String> {
+        let mut pos = 0;
+        let magic = pnm_token(buf, &mut pos).ok_or("empty image")?;
+        let (grey, ascii) = match magic {
+            b"P2" => (true, true),
+            b"P3" => (false, true),
+            b"P5" => (true, false),
+            b"P6" => (false, false),
+            _ => return Err("not a .ppm / .pgm image (P2, P3, P5 or P6)".to_string()),
+        };
+        let mut header = [0usize; 3];
+        for v in header.iter_mut() {
+            let t = pnm_token(buf, &mut pos).ok_or("image header is cut short")?;
+            *v = std::str::from_utf8(t).ok().and_then(|t| t.parse().ok()).ok_or("bad number in image header")?;
+        }
+        let [w, h, max] = header;
+        if w == 0 || h == 0 || w > 1 << 14 || h > 1 << 14 || max == 0 || max > 65535 {
+            return Err(format!("bad image header {}x{} max {}", w, h, max));
+        }
+
+        let chans = if grey { 1 } else { 3 };
+        let n = w * h * chans;
+        // the header alone can ask for gigabytes, so nothing is reserved
+        // past what the file could actually hold
+        let mut vals = Vec::with_capacity(n.min(buf.len()));
+        if ascii {
+            for _ in 0..n {
+                let t = pnm_token(buf, &mut pos).ok_or("image data is cut short")?;
+                let v: usize = std::str::from_utf8(t).ok().and_then(|t| t.parse().ok()).ok_or("bad number in image data")?;
+                vals.push(v.min(max));
+            }
+        } else {
+            // one whitespace byte between the header and the data
+            pos += 1;
+            let wide = max > 255;
+            let need = n * if wide { 2 } else { 1 };
+            if buf.len().saturating_sub(pos) < need {
+                return Err("image data is cut short".to_string());
+            }
+            let data = &buf[pos..pos + need];
+            if wide {
+                vals.extend(data.chunks_exact(2).map(|b| (u16::from_be_bytes([b[0], b[1]]) as usize).min(max)));
+            } else {
+                vals.extend(data.iter().map(|&b| (b as usize).min(max)));
+            }
+        }
+
+        let to8 = |v: usize| (v * 255 / max) as i32;
+        let cells = vals
+            .chunks_exact(chans)
+            .map(|px| if grey { classify(to8(px[0]), to8(px[0]), to8(px[0])) } else { classify(to8(px[0]), to8(px[1]), to8(px[2])) })
+            .collect();
+        Ok(Self { w, h, cells })
+    }
+
+    // nearest pixel for every cell, so any image fits any world size
+    pub fn fit(&self, sp: Space) -> Vec<Ground> {
+        let (ww, wh) = (sp.w as usize, sp.h as usize);
+        let mut out = Vec::with_capacity(sp.cells());
+        for y in 0..wh {
+            let iy = y * self.h / wh;
+            for x in 0..ww {
+                out.push(self.cells[iy * self.w + x * self.w / ww]);
+            }
+        }
+        out
+    }
+}
+
+fn classify(r: i32, g: i32, b: i32) -> Ground {
+    if r.max(g).max(b) < 70 {
+        Ground::Rock
+    } else if b > r + 40 && b > g {
+        Ground::Water
+    } else if g > r + 30 && g > b + 30 {
+        Ground::Fertile
+    } else if r > b + 60 && g > b + 30 {
+        Ground::Barren
+    } else {
+        Ground::Plain
+    }
+}
+
+// next whitespace separated token, skipping `#` comments
+fn pnm_token<'a>(buf: &'a [u8], pos: &mut usize) -> Option<&'a [u8]> {
+    loop {
+        while *pos < buf.len() && buf[*pos].is_ascii_whitespace() {
+            *pos += 1;
+        }
+        if buf.get(*pos) != Some(&b'#') {
+            break;
+        }
+        while *pos < buf.len() && buf[*pos] != b'\n' {
+            *pos += 1;
+        }
+    }
+    let start = *pos;
+    while *pos < buf.len() && !buf[*pos].is_ascii_whitespace() {
+        *pos += 1;
+    }
+    (*pos > start).then(|| &buf[start..*pos])
+}
+
+// two noise fields over the torus: height makes lakes down low and rock up
+// high, wetness splits the rest into fertile, plain and barren
+pub fn generate(sp: Space, key: u64) -> Vec<Ground> {
+    let height = noise(sp, key, 0);
+    let wet = noise(sp, key, 1);
+    height
+        .iter()
+        .zip(&wet)
+        .map(|(&e, &m)| {
+            if e < 0.34 {
+                Ground::Water
+            } else if e > 0.70 {
+                Ground::Rock
+            } else if m > 0.60 {
+                Ground::Fertile
+            } else if m < 0.40 {
+                Ground::Barren
+            } else {
+                Ground::Plain
+            }
+        })
+        .collect()
+}
+
+// value noise, a coarse and a fine octave. the lattices wrap with the world
+// so there's no seam at the edges
+fn noise(sp: Space, key: u64, salt: u64) -> Vec<f32> {
+    let mut out = vec![0.0f32; sp.cells()];
+    for (octave, (spacing, weight)) in [(24.0f32, 0.7f32), (8.0, 0.3)].into_iter().enumerate() {
+        let nx = ((sp.w as f32 / spacing).round() as usize).max(2);
+        let ny = ((sp.h as f32 / spacing).round() as usize).max(2);
+        let k = key ^ (salt * 2 + octave as u64 + 1).wrapping_mul(0xA24B_AED4_963E_E407);
+        let lattice: Vec<f32> = (0..nx * ny).map(|i| keyed_rng(k, i as u64).random::<f32>()).collect();
+        let at = |x: usize, y: usize| lattice[(y % ny) * nx + x % nx];
+
+        for y in 0..sp.h as usize {
+            let fy = (y as f32 + 0.5) / sp.h as f32 * ny as f32;
+            let (iy, ty) = (fy.floor() as usize, smooth(fy.fract()));
+            for x in 0..sp.w as usize {
+                let fx = (x as f32 + 0.5) / sp.w as f32 * nx as f32;
+                let (ix, tx) = (fx.floor() as usize, smooth(fx.fract()));
+                let top = at(ix, iy) + (at(ix + 1, iy) - at(ix, iy)) * tx;
+                let bot = at(ix, iy + 1) + (at(ix + 1, iy + 1) - at(ix, iy + 1)) * tx;
+                out[y * sp.w as usize + x] += (top + (bot - top) * ty) * weight;
+            }
+        }
+    }
+    out
+}
+
+fn smooth(t: f32) -> f32 {
+    t * t * (3.0 - 2.0 * t)
+}
+
+// one move of `step` from p. water scales it down, rock stops it. an agent
+// walking into rock slides along it if one axis is still open
+pub fn walk(sp: Space, ground: &[Ground], p: Vec2, step: Vec2) -> Vec2 {
+    let step = step * ground[sp.cell_of(p)].speed();
+    for s in [step, vec2(step.x, 0.0), vec2(0.0, step.y)] {
+        let q = sp.wrap(p + s);
+        if !ground[sp.cell_of(q)].blocks() {
+            return q;
+        }
+    }
+    p
+}
+
+// a random spot that isn't rock, None if a few tries all hit rock. on
+// ground without rock this is exactly one random_pos
+pub fn open_pos(sp: Space, ground: &[Ground], rng: &mut impl Rng) -> Option<Vec2> {
+    (0..16).map(|_| sp.random_pos(rng)).find(|&p| !ground[sp.cell_of(p)].blocks())
+}
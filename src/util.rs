@@ -1,4 +1,8 @@
-use rand09::{rngs::StdRng, SeedableRng};
+use rand09::{RngCore, SeedableRng};
+use rand_chacha::ChaCha12Rng;
+
+// same generator StdRng wraps, but this one lets us read/restore its position
+pub type SimRng = ChaCha12Rng;
 
 pub fn gen_seed(cpu_threads: usize) -> u64 {
     let t = std::time::SystemTime::now()
@@ -10,8 +14,41 @@ pub fn gen_seed(cpu_threads: usize) -> u64 {
     a ^ b.rotate_left(17) ^ 0xD1B5_4A32_D192_ED03
 }
 
-pub fn rng_from_seed(seed: u64) -> StdRng {
-    StdRng::seed_from_u64(seed)
+pub fn rng_from_seed(seed: u64) -> SimRng {
+    SimRng::seed_from_u64(seed)
+}
+
+// splitmix64, seeded from a per-step key plus an item number. lets every
+// cell/agent draw its own numbers without sharing one stream, so work can
+// be split across threads in any way and still come out the same.
+pub struct KeyedRng(u64);
+
+pub fn keyed_rng(key: u64, n: u64) -> KeyedRng {
+    KeyedRng(key ^ mix64(n.wrapping_add(0x9E37_79B9_7F4A_7C15)))
+}
+
+fn mix64(mut z: u64) -> u64 {
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+impl RngCore for KeyedRng {
+    fn next_u32(&mut self) -> u32 {
+        (self.next_u64() >> 32) as u32
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        mix64(self.0)
+    }
+
+    fn fill_bytes(&mut self, dst: &mut [u8]) {
+        for chunk in dst.chunks_mut(8) {
+            let v = self.next_u64().to_le_bytes();
+            chunk.copy_from_slice(&v[..chunk.len()]);
+        }
+    }
 }
 
 pub fn wrap_i(x: i32, m: i32) -> i32 {
@@ -43,3 +80,22 @@ pub fn fmt_compact(n: u64) -> String {
         n.to_string()
     }
 }
+
+// unix seconds -> "yyyy-mm-dd hh:mm" (utc)
+pub fn fmt_utc(secs: u64) -> String {
+    let days = (secs / 86_400) as i64;
+    let rem = secs % 86_400;
+
+    // civil from days, howard hinnant's algorithm
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let d = doy - (153 * mp + 2) / 5 + 1;
+    let m = if mp < 10 { mp + 3 } else { mp - 9 };
+    let y = yoe + era * 400 + if m <= 2 { 1 } else { 0 };
+
+    format!("{:04}-{:02}-{:02} {:02}:{:02}", y, m, d, rem / 3_600, (rem % 3_600) / 60)
+}
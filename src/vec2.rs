@@ -0,0 +1,60 @@
+use std::ops::{Add, AddAssign, Div, Mul, Sub};
+
+// tiny 2d vector so the sim core doesn't need a graphics crate
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Vec2 {
+    pub x: f32,
+    pub y: f32,
+}
+
+pub const fn vec2(x: f32, y: f32) -> Vec2 {
+    Vec2 { x, y }
+}
+
+impl Vec2 {
+    pub const ZERO: Self = vec2(0.0, 0.0);
+
+    pub fn dot(self, o: Self) -> f32 {
+        (self.x * o.x) + (self.y * o.y)
+    }
+
+    pub fn length(self) -> f32 {
+        self.dot(self).sqrt()
+    }
+}
+
+impl Add for Vec2 {
+    type Output = Self;
+    fn add(self, o: Self) -> Self {
+        vec2(self.x + o.x, self.y + o.y)
+    }
+}
+
+impl AddAssign for Vec2 {
+    fn add_assign(&mut self, o: Self) {
+        self.x += o.x;
+        self.y += o.y;
+    }
+}
+
+impl Sub for Vec2 {
+    type Output = Self;
+    fn sub(self, o: Self) -> Self {
+        vec2(self.x - o.x, self.y - o.y)
+    }
+}
+
+impl Mul<f32> for Vec2 {
+    type Output = Self;
+    fn mul(self, s: f32) -> Self {
+        vec2(self.x * s, self.y * s)
+    }
+}
+
+impl Div<f32> for Vec2 {
+    type Output = Self;
+    fn div(self, s: f32) -> Self {
+        vec2(self.x / s, self.y / s)
+    }
+}
@@ -1,308 +1,502 @@
-use std::fs::File;
-use std::io::{Read, Write};
-
-use macroquad::prelude::*;
 use rand09::Rng;
 
 use crate::config::*;
+use crate::genome::Genome;
+use crate::lineage::Lineage;
+use crate::par;
+use crate::save::{self, RunState, SaveData, SaveError};
+use crate::seasons;
+use crate::soil;
+use crate::spatial::SpatialGrid;
+use crate::species::{self, Species, SpeciesDef};
+use crate::stats::{Counts, Deltas, PopCounts, TraitStats};
+use crate::terrain::{self, Ground};
 use crate::util::*;
+use crate::vec2::{Vec2, vec2};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct TrackTarget {
+    pub species: usize,
+    pub id: u32,
+}
+
+// what happens at the edge of the world
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum Boundary {
+    // leave one side, come in the other
+    #[default]
+    Torus,
+    // walls that bounce whoever walks into them back the way they came
+    Reflect,
+    // walls that stop whoever walks into them
+    Closed,
+}
+
+impl Boundary {
+    pub const ALL: [Boundary; 3] = [Boundary::Torus, Boundary::Reflect, Boundary::Closed];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Boundary::Torus => "torus",
+            Boundary::Reflect => "reflect",
+            Boundary::Closed => "closed",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|b| b.name() == s)
+    }
+}
+
+// world size plus the edge math that goes with it
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Space {
+    pub w: i32,
+    pub h: i32,
+    pub edge: Boundary,
+}
+
+impl Space {
+    pub fn new(w: i32, h: i32) -> Self {
+        Self { w, h, edge: Boundary::Torus }
+    }
+
+    pub fn with_edge(self, edge: Boundary) -> Self {
+        Self { edge, ..self }
+    }
+
+    pub fn wraps(self) -> bool {
+        self.edge == Boundary::Torus
+    }
+
+    pub fn cells(self) -> usize {
+        (self.w * self.h) as usize
+    }
+
+    // for cells already inside the world, or on a torus
+    pub fn idx(self, x: i32, y: i32) -> usize {
+        (wrap_i(y, self.h) * self.w + wrap_i(x, self.w)) as usize
+    }
+
+    // a neighbour of some cell, None past a wall
+    pub fn cell(self, x: i32, y: i32) -> Option<usize> {
+        if self.wraps() || ((0..self.w).contains(&x) && (0..self.h).contains(&y)) {
+            Some(self.idx(x, y))
+        } else {
+            None
+        }
+    }
+
+    pub fn cell_of(self, p: Vec2) -> usize {
+        self.idx(p.x.floor() as i32, p.y.floor() as i32)
+    }
+
+    // back inside after a move: across the wrap, bounced off a wall or
+    // stopped at it
+    pub fn wrap(self, p: Vec2) -> Vec2 {
+        let (w, h) = (self.w as f32, self.h as f32);
+        match self.edge {
+            Boundary::Torus => vec2(wrap_f(p.x, w), wrap_f(p.y, h)),
+            Boundary::Reflect => vec2(reflect(p.x, w), reflect(p.y, h)),
+            Boundary::Closed => vec2(inside(p.x, w), inside(p.y, h)),
+        }
+    }
+
+    pub fn random_pos(self, rng: &mut impl Rng) -> Vec2 {
+        vec2(rng.random::<f32>() * self.w as f32, rng.random::<f32>() * self.h as f32)
+    }
+
+    // shortest a -> b, across the wrap on a torus
+    pub fn delta(self, a: Vec2, b: Vec2) -> Vec2 {
+        if !self.wraps() {
+            return b - a;
+        }
+        let w = self.w as f32;
+        let h = self.h as f32;
+
+        let mut dx = b.x - a.x;
+        let mut dy = b.y - a.y;
+
+        if dx > w * 0.5 { dx -= w; }
+        if dx < -w * 0.5 { dx += w; }
+        if dy > h * 0.5 { dy -= h; }
+        if dy < -h * 0.5 { dy += h; }
+
+        vec2(dx, dy)
+    }
+
+    pub fn dist(self, a: Vec2, b: Vec2) -> f32 {
+        self.delta(a, b).length()
+    }
+}
+
+// 0..m, just short of m so the cell stays inside
+fn inside(x: f32, m: f32) -> f32 {
+    x.clamp(0.0, m * (1.0 - f32::EPSILON))
+}
+
+// mirrored back in off whichever wall it went past
+fn reflect(x: f32, m: f32) -> f32 {
+    let x = if x < 0.0 { -x } else if x >= m { 2.0 * m - x } else { x };
+    inside(x, m)
+}
 
 #[derive(Clone, Copy)]
-struct Agent {
-    id: u32,
-    p: Vec2,
-    pp: Vec2,
-    e: f32,
-    age: u32,
+pub struct Agent {
+    pub id: u32,
+    pub p: Vec2,
+    pub pp: Vec2,
+    pub e: f32,
+    pub age: u32,
+    pub g: Genome,
+    // 0 when it wasn't born to anyone, see lineage.rs
+    pub parent: u32,
+    // world step it was born on
+    pub born: u64,
 }
 
 pub struct TrackedInfo {
-    pub kind: TrackKind,
+    pub species: usize,
     pub id: u32,
     pub e: f32,
     pub age: u32,
     pub p: Vec2,
+    pub g: Genome,
+    pub parent: u32,
+    pub born: u64,
+    // how far it looks for things to flee / chase, None if it has none
+    pub flee_r: Option<f32>,
+    pub chase_r: Option<f32>,
+    // nearest threat and prey inside those, what steers it
+    pub threat_at: Option<Vec2>,
+    pub prey_at: Option<Vec2>,
+    // last step's move
+    pub heading: Vec2,
 }
 
+// what happened to the watched agent during a step, see World::watch
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum LifeEvent {
+    // killed one of this species
+    Ate(usize),
+    // energy taken from plants this step
+    Grazed(f32),
+    // had a kid with this id
+    Bred(u32),
+    // one of this species got within twice the eat radius and missed
+    NearMiss(usize),
+    Starved,
+    Eaten(usize),
+    // taken out with the remove tool
+    Removed,
+}
+
+// species parameters live in the world (they decide what its agent lists
+// mean), SimSettings only supplies the global knobs when stepping
 pub struct World {
-    plants: Vec<u8>,
-    herbs: Vec<Agent>,
-    preds: Vec<Agent>,
-    next_id: u32,
+    pub(crate) space: Space,
+    pub(crate) plants: Vec<u8>,
+    // never changes once the world is made
+    pub(crate) ground: Vec<Ground>,
+    // nutrients per cell, only touched with soil on
+    pub(crate) soil: Vec<u8>,
+    pub(crate) species: Vec<Species>,
+    // one agent list per species
+    pub(crate) pops: Vec<Vec<Agent>>,
+    pub(crate) next_id: u32,
+    order: Vec<usize>,
+    // neighbour lookup scratch, rebuilt every phase
+    prey_grid: SpatialGrid,
+    threat_grid: SpatialGrid,
+    // one agent whose life gets logged while stepping, for the gui.
+    // doesn't touch the rng so it can't change the run
+    pub(crate) watch: Option<TrackTarget>,
+    pub(crate) life: Vec<LifeEvent>,
+    // steps since the world was made, births and deaths are stamped with it
+    pub(crate) now: u64,
+    pub(crate) lineage: Lineage,
+    // daylight on the last step, see seasons.rs. kept for tracked_info,
+    // stepping works it out fresh
+    light: f32,
 }
 
+// dead lines get dropped from the lineage store this often
+const PRUNE_EVERY: u64 = 500;
+
 impl World {
-    pub fn new(rng: &mut impl Rng, set: SimSettings) -> Self {
-        let n = (W * H) as usize;
+    pub fn new(rng: &mut impl Rng, set: &SimSettings) -> Self {
+        let space = Space::new(set.world_w, set.world_h).with_edge(set.boundary);
+        let n = space.cells();
 
         let mut plants = vec![0u8; n];
-        for i in 0..n {
+        for v in plants.iter_mut() {
             let r: f32 = rng.random();
-            plants[i] = if r < 0.55 {
+            *v = if r < 0.55 {
                 (r * 255.0) as u8
             } else {
                 (180.0 + 75.0 * rng.random::<f32>()) as u8
             };
         }
 
-        let mut next_id = 1u32;
-
-        let mut herbs = Vec::new();
-        let mut preds = Vec::new();
-
-        for _ in 0..set.init_herbs {
-            let p = vec2(rng.random::<f32>() * W as f32, rng.random::<f32>() * H as f32);
-            herbs.push(Agent { id: next_id, p, pp: p, e: 1.2 + 0.6 * rng.random::<f32>(), age: 0 });
-            next_id += 1;
-        }
-
-        for _ in 0..set.init_preds {
-            let p = vec2(rng.random::<f32>() * W as f32, rng.random::<f32>() * H as f32);
-            preds.push(Agent { id: next_id, p, pp: p, e: 1.6 + 0.8 * rng.random::<f32>(), age: 0 });
-            next_id += 1;
+        let ground = set.terrain.build(space, rng);
+        for (v, g) in plants.iter_mut().zip(&ground) {
+            if !g.grows() {
+                *v = 0;
+            }
         }
 
-        Self { plants, herbs, preds, next_id }
-    }
-
-    pub fn counts(&self) -> Counts {
-        let mut s = 0u64;
-        for &v in &self.plants {
-            s += v as u64;
-        }
-        let plants_avg = (s as f32) / (self.plants.len() as f32) / 255.0;
+        let mut next_id = 1u32;
 
-        let mut he = 0.0f32;
-        for a in &self.herbs {
-            he += a.e;
+        let mut pops = Vec::new();
+        for d in &set.species {
+            let mut list = Vec::new();
+            for _ in 0..d.init {
+                let Some(p) = terrain::open_pos(space, &ground, rng) else {
+                    continue;
+                };
+                let e = d.init_e + d.init_e_var * rng.random::<f32>();
+                let g = Genome::BASE.mutate(rng, set.mutation);
+                list.push(Agent { id: next_id, p, pp: p, e, age: 0, g, parent: 0, born: 0 });
+                next_id += 1;
+            }
+            pops.push(list);
         }
-        let herb_e_avg = if self.herbs.is_empty() { 0.0 } else { he / self.herbs.len() as f32 };
 
-        let mut pe = 0.0f32;
-        for a in &self.preds {
-            pe += a.e;
-        }
-        let pred_e_avg = if self.preds.is_empty() { 0.0 } else { pe / self.preds.len() as f32 };
-
-        Counts {
-            plants_avg,
-            herbs: self.herbs.len() as u32,
-            preds: self.preds.len() as u32,
-            herb_e_avg,
-            pred_e_avg,
-        }
+        let mut w = Self::from_parts(space, plants, ground, species::resolve(&set.species), pops, next_id);
+        w.light = seasons::light(set, 0);
+        w.soil = vec![set.soil_start; n];
+        w
     }
 
-    pub fn tracked_info(&self, t: TrackTarget) -> Option<TrackedInfo> {
-        match t.kind {
-            TrackKind::Herb => {
-                for a in &self.herbs {
-                    if a.id == t.id {
-                        return Some(TrackedInfo { kind: TrackKind::Herb, id: a.id, e: a.e, age: a.age, p: a.p });
-                    }
-                }
-                None
-            }
-            TrackKind::Pred => {
-                for a in &self.preds {
-                    if a.id == t.id {
-                        return Some(TrackedInfo { kind: TrackKind::Pred, id: a.id, e: a.e, age: a.age, p: a.p });
-                    }
-                }
-                None
+    pub(crate) fn from_parts(
+        space: Space,
+        plants: Vec<u8>,
+        ground: Vec<Ground>,
+        species: Vec<Species>,
+        mut pops: Vec<Vec<Agent>>,
+        next_id: u32,
+    ) -> Self {
+        pops.resize_with(species.len(), Vec::new);
+        // the living get records, whoever came before them is unknown here
+        let mut lineage = Lineage::new();
+        for (s, list) in pops.iter().enumerate() {
+            for a in list {
+                lineage.born(a.id, a.parent, s, a.born);
             }
         }
+        Self {
+            space,
+            plants,
+            ground,
+            soil: vec![soil::MID; space.cells()],
+            order: species::step_order(&species),
+            species,
+            pops,
+            next_id,
+            prey_grid: SpatialGrid::default(),
+            threat_grid: SpatialGrid::default(),
+            watch: None,
+            life: Vec::new(),
+            now: 0,
+            lineage,
+            light: 1.0,
+        }
     }
 
-    pub fn pick_target(&self, world_pos: Vec2) -> Option<TrackTarget> {
-        let mut best: Option<TrackTarget> = None;
-        let mut bestd = 9999.0f32;
+    pub fn space(&self) -> Space {
+        self.space
+    }
 
-        let r = 1.25;
+    pub fn plants(&self) -> &[u8] {
+        &self.plants
+    }
 
-        for a in &self.herbs {
-            let d = toroid_dist(world_pos, a.p);
-            if d < r && d < bestd {
-                bestd = d;
-                best = Some(TrackTarget { kind: TrackKind::Herb, id: a.id });
-            }
-        }
+    pub fn ground(&self) -> &[Ground] {
+        &self.ground
+    }
 
-        for a in &self.preds {
-            let d = toroid_dist(world_pos, a.p);
-            if d < r && d < bestd {
-                bestd = d;
-                best = Some(TrackTarget { kind: TrackKind::Pred, id: a.id });
-            }
-        }
+    pub fn soil(&self) -> &[u8] {
+        &self.soil
+    }
 
-        best
+    pub fn species(&self) -> &[Species] {
+        &self.species
     }
 
-    pub fn step(&mut self, rng: &mut impl Rng, set: SimSettings, _dt: f32) -> Deltas {
-        self.plants_step(rng, set);
-        let eaten = self.preds_step(rng, set);
-        self.herbs_step(rng, set);
-        self.cleanup_repro(rng, set, eaten)
+    pub fn species_names(&self) -> Vec<String> {
+        self.species.iter().map(|s| s.def.name.clone()).collect()
     }
 
-    pub fn draw(&self, layout: &Layout, alpha: f32, tracked: Option<TrackTarget>) {
-        draw_rectangle(0.0, 0.0, layout.world_w_px, layout.world_h_px, GRID_BG);
+    pub fn agents(&self, s: usize) -> &[Agent] {
+        self.pops.get(s).map(|v| v.as_slice()).unwrap_or(&[])
+    }
 
-        for y in 0..H {
-            for x in 0..W {
-                let i = (y * W + x) as usize;
-                let v = self.plants[i] as f32 / 255.0;
-                if v <= 0.01 {
-                    continue;
-                }
-                let col = Color::new(
-                    lerp(GRID_BG.r, C_PLANT.r, v),
-                    lerp(GRID_BG.g, C_PLANT.g, v),
-                    lerp(GRID_BG.b, C_PLANT.b, v),
-                    1.0,
-                );
-                draw_rectangle((x as f32) * CELL, (y as f32) * CELL, CELL, CELL, col);
-            }
+    pub fn counts(&self) -> Counts {
+        let mut s = 0u64;
+        for &v in &self.plants {
+            s += v as u64;
         }
+        let plants_avg = (s as f32) / (self.plants.len() as f32) / 255.0;
 
-        let mut tracked_px: Option<Vec2> = None;
+        let pops = self
+            .pops
+            .iter()
+            .map(|list| {
+                let mut e = 0.0f32;
+                for a in list {
+                    e += a.e;
+                }
+                PopCounts {
+                    n: list.len() as u32,
+                    e_avg: if list.is_empty() { 0.0 } else { e / list.len() as f32 },
+                    traits: trait_stats(list),
+                }
+            })
+            .collect();
 
-        for a in &self.herbs {
-            let p = interp_agent(a, alpha);
-            let px = p.x * CELL + CELL * 0.5;
-            let py = p.y * CELL + CELL * 0.5;
-            draw_circle(px, py, CELL * 0.42, C_HERB);
-            if tracked == Some(TrackTarget { kind: TrackKind::Herb, id: a.id }) {
-                tracked_px = Some(vec2(px, py));
-            }
-        }
+        Counts { plants_avg, pops }
+    }
 
-        for a in &self.preds {
-            let p = interp_agent(a, alpha);
-            let px = p.x * CELL + CELL * 0.5;
-            let py = p.y * CELL + CELL * 0.5;
-            draw_poly(px, py, 3, CELL * 0.55, 0.0, C_PRED);
-            if tracked == Some(TrackTarget { kind: TrackKind::Pred, id: a.id }) {
-                tracked_px = Some(vec2(px, py));
-            }
-        }
+    pub fn tracked_info(&self, t: TrackTarget) -> Option<TrackedInfo> {
+        let a = self.agents(t.species).iter().find(|a| a.id == t.id)?;
+        let spc = &self.species[t.species];
+        let sight = seasons::at_night(spc.def.night_sight, self.light);
+        let flee_r = (!spc.threats.is_empty()).then_some(spc.def.flee_radius * a.g.sense * sight);
+        let chase_r = (!spc.prey.is_empty()).then_some(spc.def.chase_radius * a.g.sense * sight);
+        Some(TrackedInfo {
+            species: t.species,
+            id: a.id,
+            e: a.e,
+            age: a.age,
+            p: a.p,
+            g: a.g,
+            parent: a.parent,
+            born: a.born,
+            flee_r,
+            chase_r,
+            threat_at: flee_r.and_then(|r| self.nearest_of(&spc.threats, a.p, r)),
+            prey_at: chase_r.and_then(|r| self.nearest_of(&spc.prey, a.p, r)),
+            heading: self.space.delta(a.pp, a.p),
+        })
+    }
 
-        if let Some(tp) = tracked_px {
-            draw_circle_lines(tp.x, tp.y, CELL * 0.78, 3.0, Color::new(0.95, 0.95, 1.0, 0.90));
-            draw_circle_lines(tp.x, tp.y, CELL * 0.98, 2.0, Color::new(0.20, 0.60, 1.0, 0.65));
-        }
+    // nearest agent of any of these species within r. brute force, only
+    // the tracked agent asks
+    fn nearest_of(&self, species: &[usize], p: Vec2, r: f32) -> Option<Vec2> {
+        species
+            .iter()
+            .flat_map(|&s| self.pops[s].iter())
+            .map(|a| (a.p, self.space.dist(p, a.p)))
+            .filter(|&(_, d)| d <= r)
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(q, _)| q)
+    }
 
-        draw_rectangle_lines(0.0, 0.0, layout.world_w_px, layout.world_h_px, 2.0, LINE);
+    // a species the world doesn't have watches nothing
+    pub fn watch(&mut self, t: Option<TrackTarget>) {
+        self.watch = t.filter(|t| t.species < self.species.len());
+        self.life.clear();
     }
 
-    pub fn save(&self, path: &str, set: SimSettings) -> bool {
-        let mut f = match File::create(path) {
-            Ok(v) => v,
-            Err(_) => return false,
-        };
+    pub fn now(&self) -> u64 {
+        self.now
+    }
 
-        if f.write_all(b"ECO3").is_err() {
-            return false;
-        }
+    pub fn light(&self) -> f32 {
+        self.light
+    }
 
-        if write_i32(&mut f, W).is_err() || write_i32(&mut f, H).is_err() {
-            return false;
-        }
+    pub fn lineage(&self) -> &Lineage {
+        &self.lineage
+    }
 
-        if write_settings(&mut f, set).is_err() {
-            return false;
-        }
+    pub fn watching(&self) -> Option<TrackTarget> {
+        self.watch
+    }
 
-        if write_u32(&mut f, self.next_id).is_err() {
-            return false;
-        }
+    // events for the watched agent since the last call
+    pub fn take_life_events(&mut self) -> Vec<LifeEvent> {
+        std::mem::take(&mut self.life)
+    }
 
-        if write_u32(&mut f, self.plants.len() as u32).is_err() {
-            return false;
-        }
-        if f.write_all(&self.plants).is_err() {
-            return false;
-        }
+    pub fn pick_target(&self, world_pos: Vec2) -> Option<TrackTarget> {
+        let mut best: Option<TrackTarget> = None;
+        let mut bestd = 9999.0f32;
 
-        if write_u32(&mut f, self.herbs.len() as u32).is_err() {
-            return false;
-        }
-        for a in &self.herbs {
-            if write_agent(&mut f, a).is_err() {
-                return false;
-            }
-        }
+        let r = 1.25;
 
-        if write_u32(&mut f, self.preds.len() as u32).is_err() {
-            return false;
-        }
-        for a in &self.preds {
-            if write_agent(&mut f, a).is_err() {
-                return false;
+        for (s, list) in self.pops.iter().enumerate() {
+            for a in list {
+                let d = self.space.dist(world_pos, a.p);
+                if d < r && d < bestd {
+                    bestd = d;
+                    best = Some(TrackTarget { species: s, id: a.id });
+                }
             }
         }
 
-        true
+        best
     }
 
-    pub fn load(path: &str) -> Option<(Self, SimSettings)> {
-        let mut f = File::open(path).ok()?;
-        let mut magic = [0u8; 4];
-        f.read_exact(&mut magic).ok()?;
-        if &magic != b"ECO3" {
-            return None;
-        }
+    pub fn step(&mut self, rng: &mut impl Rng, set: &SimSettings, _dt: f32) -> Deltas {
+        let threads = par::threads_for(set.threads);
+        self.now += 1;
+        self.light = seasons::light(set, self.now);
+        self.plants_step(rng, set, threads);
 
-        let w = read_i32(&mut f).ok()?;
-        let h = read_i32(&mut f).ok()?;
-        if w != W || h != H {
-            return None;
+        let mut killed = vec![0u32; self.species.len()];
+        for k in 0..self.order.len() {
+            let s = self.order[k];
+            self.species_step(s, rng, set, threads, &mut killed);
         }
 
-        let set = read_settings(&mut f).ok()?;
-        let next_id = read_u32(&mut f).ok()?;
-
-        let n = read_u32(&mut f).ok()? as usize;
-        if n != (W * H) as usize {
-            return None;
-        }
-
-        let mut plants = vec![0u8; n];
-        f.read_exact(&mut plants).ok()?;
-
-        let hn = read_u32(&mut f).ok()? as usize;
-        let mut herbs = Vec::with_capacity(hn);
-        for _ in 0..hn {
-            herbs.push(read_agent(&mut f).ok()?);
-        }
-
-        let pn = read_u32(&mut f).ok()? as usize;
-        let mut preds = Vec::with_capacity(pn);
-        for _ in 0..pn {
-            preds.push(read_agent(&mut f).ok()?);
+        let d = self.cleanup_repro(rng, set, &killed);
+        if self.now.is_multiple_of(PRUNE_EVERY) {
+            self.lineage.prune(self.watch.map(|w| w.id));
         }
+        d
+    }
 
-        Some((Self { plants, herbs, preds, next_id }, set))
+    pub fn save(&self, path: &str, set: &SimSettings, run: &RunState) -> Result<(), SaveError> {
+        save::write(path, self, set, run)
     }
 
-    fn plants_step(&mut self, rng: &mut impl Rng, set: SimSettings) {
-        let n = self.plants.len();
-        let grow = set.plant_grow as i32;
+    pub fn load(path: &str) -> Result<SaveData, SaveError> {
+        save::read(path)
+    }
 
-        for i in 0..n {
-            let v = self.plants[i] as i32;
-            let jitter = (2.0 * rng.random::<f32>()) as i32;
-            self.plants[i] = (v + grow + jitter).min(255) as u8;
-        }
+    fn plants_step(&mut self, rng: &mut impl Rng, set: &SimSettings, threads: usize) {
+        let sp = self.space;
+        let season = seasons::growth(set, self.now);
+        let grow = (set.plant_grow as f32 * season).round() as i32;
+        let spread = (set.plant_spread * season).min(1.0);
+
+        if set.soil && set.soil_drift > 0 && self.now.is_multiple_of(set.soil_drift as u64) {
+            soil::drift(&mut self.soil, set.soil_start);
+        }
+
+        let ground = &self.ground;
+        let nutrients = set.soil.then_some(self.soil.as_slice());
+        let key = rng.random::<u64>();
+        par::for_each_chunk(&mut self.plants, threads, 4096, |off, chunk| {
+            for (k, pv) in chunk.iter_mut().enumerate() {
+                let jitter = (2.0 * keyed_rng(key, (off + k) as u64).random::<f32>()) as i32;
+                let mut g = ground[off + k].growth(grow, jitter);
+                if let Some(n) = nutrients {
+                    g = soil::growth(g, n[off + k]);
+                }
+                *pv = (*pv as i32 + g).min(255) as u8;
+            }
+        });
 
-        let tries = (W * H / 6) as usize;
+        // spreading touches neighbours, so it stays on the main stream
+        let tries = sp.cells() / 6;
         for _ in 0..tries {
-            let x = (rng.random::<f32>() * W as f32) as i32;
-            let y = (rng.random::<f32>() * H as f32) as i32;
-            let i = (y * W + x) as usize;
+            let x = (rng.random::<f32>() * sp.w as f32) as i32;
+            let y = (rng.random::<f32>() * sp.h as f32) as i32;
+            let i = sp.idx(x, y);
 
-            if self.plants[i] < 110 {
+            if self.plants[i] < set.spread_from {
                 continue;
             }
 
@@ -312,173 +506,267 @@ impl World {
                 continue;
             }
 
-            let xx = wrap_i(x + dx, W);
-            let yy = wrap_i(y + dy, H);
-            let j = (yy * W + xx) as usize;
+            let Some(j) = sp.cell(x + dx, y + dy) else {
+                continue;
+            };
 
-            if self.plants[j] < 60 && rng.random::<f32>() < set.plant_spread {
-                self.plants[j] = (self.plants[j] + 45).min(255);
+            if self.plants[j] < set.spread_into && self.ground[j].grows() && rng.random::<f32>() < spread {
+                self.plants[j] = self.plants[j].saturating_add(set.spread_amount);
             }
         }
     }
 
-    fn herbs_step(&mut self, rng: &mut impl Rng, set: SimSettings) {
-        let preds_pos: Vec<Vec2> = self.preds.iter().map(|p| p.p).collect();
-        let speed = set.herb_speed;
-        let plants = &mut self.plants;
+    // one species moves, then hunts and grazes. everyone decides against
+    // the same snapshot, eating is resolved in list order afterwards.
+    fn species_step(&mut self, s: usize, rng: &mut impl Rng, set: &SimSettings, threads: usize, killed: &mut [u32]) {
+        let sp = self.space;
+        let World { plants, ground, soil: nutrients, species, pops, prey_grid, threat_grid, watch, life, now, lineage, .. } = self;
+        let now = *now;
+        let watch = *watch;
+        let spc = &species[s];
+        let d = &spc.def;
+        let sight = seasons::at_night(d.night_sight, self.light);
+        let pace = seasons::at_night(d.night_speed, self.light);
+
+        let threat_pos = gather(pops, &spc.threats);
+        threat_grid.rebuild(sp, &threat_pos);
+        let prey_pos = gather(pops, &spc.prey);
+        prey_grid.rebuild(sp, &prey_pos);
+
+        let look = Senses {
+            plants: if spc.eats_plants { Some(plants.as_slice()) } else { None },
+            threats: (!spc.threats.is_empty()).then_some((threat_pos.as_slice(), &*threat_grid)),
+            prey: (!spc.prey.is_empty()).then_some((prey_pos.as_slice(), &*prey_grid)),
+            sight,
+        };
 
-        for h in &mut self.herbs {
-            h.pp = h.p;
-            h.e -= set.herb_met;
-            h.age = h.age.saturating_add(1);
+        let key = rng.random::<u64>();
+        par::for_each_chunk(&mut pops[s], threads, 256, |_, chunk| {
+            for a in chunk {
+                a.pp = a.p;
+                a.e -= d.met * a.g.upkeep();
+                a.age = a.age.saturating_add(1);
 
-            let dir = herb_dir(h.p, &*plants, &preds_pos, rng);
-            h.p.x = wrap_f(h.p.x + dir.x * speed, W as f32);
-            h.p.y = wrap_f(h.p.y + dir.y * speed, H as f32);
+                let mut r = keyed_rng(key, a.id as u64);
+                let dir = agent_dir(sp, a.p, a.g, d, &look, &mut r);
+                a.p = terrain::walk(sp, ground, a.p, dir * (d.speed * a.g.speed * pace));
+            }
+        });
+
+        if !spc.prey.is_empty() {
+            // species of each prey_pos entry and where the watched agent is
+            // in it, only worked out while someone is watching
+            let (prey_of, watched_prey) = match watch {
+                Some(w) => {
+                    let prey_of: Vec<usize> = spc.prey.iter().flat_map(|&q| std::iter::repeat_n(q, pops[q].len())).collect();
+                    let i = pops[w.species].iter().position(|a| a.id == w.id);
+                    let off = prey_of.iter().position(|&q| q == w.species);
+                    (prey_of, off.zip(i).map(|(o, i)| o + i))
+                }
+                None => (Vec::new(), None),
+            };
 
-            let cx = wrap_i(h.p.x.floor() as i32, W);
-            let cy = wrap_i(h.p.y.floor() as i32, H);
-            let i = (cy * W + cx) as usize;
+            // first come first served, in list order
+            let mut alive = vec![true; prey_pos.len()];
+            let mut any = false;
+            for a in pops[s].iter_mut() {
+                if let Some((i, _)) = prey_grid.nearest(sp, &prey_pos, a.p, set.eat_radius, |i| alive[i]) {
+                    alive[i] = false;
+                    a.e += d.kill_e;
+                    any = true;
+                    if watched_prey == Some(i) {
+                        life.push(LifeEvent::Eaten(s));
+                    }
+                    if watch == Some(TrackTarget { species: s, id: a.id }) {
+                        life.push(LifeEvent::Ate(prey_of[i]));
+                    }
+                }
+            }
 
-            let bite = 16u8;
-            let avail = plants[i];
-            let take = avail.min(bite);
-            plants[i] = avail - take;
+            if let Some(i) = watched_prey
+                && alive[i]
+                && pops[s].iter().any(|a| sp.dist(a.p, prey_pos[i]) <= set.eat_radius * 2.0)
+            {
+                life.push(LifeEvent::NearMiss(s));
+            }
 
-            h.e += (take as f32) * 0.0022;
+            if any {
+                let mut off = 0;
+                for &q in &spc.prey {
+                    let n = pops[q].len();
+                    let mut mask = alive[off..off + n].iter();
+                    pops[q].retain(|a| {
+                        let keep = *mask.next().unwrap_or(&true);
+                        if !keep {
+                            lineage.died(a.id, now);
+                            if set.soil {
+                                soil::deposit(nutrients, sp, a.p, set.soil_corpse);
+                            }
+                        }
+                        keep
+                    });
+                    killed[q] += (n - pops[q].len()) as u32;
+                    off += n;
+                }
+            }
         }
-    }
-
-    fn preds_step(&mut self, rng: &mut impl Rng, set: SimSettings) -> u32 {
-        let mut herb_pos: Vec<Vec2> = self.herbs.iter().map(|h| h.p).collect();
-        let speed = set.pred_speed;
-        let eat_r = set.eat_radius;
-
-        let mut eaten = 0u32;
 
-        let mut pi = 0usize;
-        while pi < self.preds.len() {
-            let p = &mut self.preds[pi];
-            p.pp = p.p;
-            p.e -= set.pred_met;
-            p.age = p.age.saturating_add(1);
+        if spc.eats_plants {
+            for a in pops[s].iter_mut() {
+                let i = sp.cell_of(a.p);
 
-            let dir = pred_dir(p.p, &herb_pos, rng);
-            p.p.x = wrap_f(p.p.x + dir.x * speed, W as f32);
-            p.p.y = wrap_f(p.p.y + dir.y * speed, H as f32);
+                let avail = plants[i];
+                let take = avail.min(d.bite);
+                plants[i] = avail - take;
+                if set.soil {
+                    nutrients[i] = nutrients[i].saturating_sub(soil::drain(take, set.soil_graze));
+                }
 
-            if let Some(hi) = nearest_within(p.p, &herb_pos, eat_r) {
-                self.herbs.swap_remove(hi);
-                herb_pos.swap_remove(hi);
-                p.e += 0.85;
-                eaten += 1;
+                a.e += (take as f32) * d.graze_e;
+                if take > 0 && watch == Some(TrackTarget { species: s, id: a.id }) {
+                    life.push(LifeEvent::Grazed(take as f32 * d.graze_e));
+                }
             }
-
-            pi += 1;
         }
-
-        eaten
     }
 
-    fn cleanup_repro(&mut self, rng: &mut impl Rng, _set: SimSettings, eaten: u32) -> Deltas {
-        let herb_before = self.herbs.len() as u32;
-        let pred_before = self.preds.len() as u32;
-
-        self.herbs.retain(|h| h.e > 0.0);
-        self.preds.retain(|p| p.e > 0.0);
-
-        let herb_after = self.herbs.len() as u32;
-        let pred_after = self.preds.len() as u32;
-
-        let mut herb_birth = 0u32;
-        let mut pred_birth = 0u32;
-
-        let mut new_herbs = Vec::new();
-        for h in &mut self.herbs {
-            if h.e > 2.2 && rng.random::<f32>() < 0.10 {
-                h.e *= 0.62;
-                let jitter = vec2(rng.random::<f32>() - 0.5, rng.random::<f32>() - 0.5) * 0.9;
-                let np = vec2(wrap_f(h.p.x + jitter.x, W as f32), wrap_f(h.p.y + jitter.y, H as f32));
-                let id = self.next_id;
-                self.next_id = self.next_id.saturating_add(1);
-                new_herbs.push(Agent { id, p: np, pp: np, e: 1.0, age: 0 });
-                herb_birth += 1;
-            }
-        }
-        self.herbs.extend(new_herbs);
-
-        let mut new_preds = Vec::new();
-        for p in &mut self.preds {
-            if p.e > 2.7 && rng.random::<f32>() < 0.08 {
-                p.e *= 0.64;
-                let jitter = vec2(rng.random::<f32>() - 0.5, rng.random::<f32>() - 0.5) * 0.8;
-                let np = vec2(wrap_f(p.p.x + jitter.x, W as f32), wrap_f(p.p.y + jitter.y, H as f32));
-                let id = self.next_id;
-                self.next_id = self.next_id.saturating_add(1);
-                new_preds.push(Agent { id, p: np, pp: np, e: 1.2, age: 0 });
-                pred_birth += 1;
-            }
-        }
-        self.preds.extend(new_preds);
-
-        if self.herbs.len() < 20 && rng.random::<f32>() < 0.25 {
-            for _ in 0..18 {
-                let p = vec2(rng.random::<f32>() * W as f32, rng.random::<f32>() * H as f32);
-                let id = self.next_id;
-                self.next_id = self.next_id.saturating_add(1);
-                self.herbs.push(Agent { id, p, pp: p, e: 1.4, age: 0 });
-                herb_birth += 1;
+    fn cleanup_repro(&mut self, rng: &mut impl Rng, set: &SimSettings, killed: &[u32]) -> Deltas {
+        let sp = self.space;
+        let n = self.species.len();
+
+        let watch = self.watch;
+        let now = self.now;
+        let ground = &self.ground;
+        let life = &mut self.life;
+        let lineage = &mut self.lineage;
+        let nutrients = &mut self.soil;
+        let watched = |s: usize, a: &Agent| watch == Some(TrackTarget { species: s, id: a.id });
+
+        let mut deaths = vec![0u32; n];
+        for (s, list) in self.pops.iter_mut().enumerate() {
+            let before = list.len();
+            list.retain(|a| {
+                if a.e <= 0.0 {
+                    lineage.died(a.id, now);
+                    // what it was made of goes back into the ground
+                    if set.soil {
+                        soil::deposit(nutrients, sp, a.p, set.soil_corpse);
+                    }
+                    if watched(s, a) {
+                        life.push(LifeEvent::Starved);
+                    }
+                }
+                a.e > 0.0
+            });
+            deaths[s] = (before - list.len()) as u32 + killed[s];
+        }
+
+        let mut births = vec![0u32; n];
+
+        for (s, ((spec, list), born)) in self.species.iter().zip(self.pops.iter_mut()).zip(births.iter_mut()).enumerate() {
+            let d = &spec.def;
+            let mut kids = Vec::new();
+            for a in list.iter_mut() {
+                // a higher threshold means waiting longer but giving the kid more
+                if a.e > d.repro_e * a.g.repro && rng.random::<f32>() < d.repro_p {
+                    a.e *= d.repro_keep;
+                    let jitter = vec2(rng.random::<f32>() - 0.5, rng.random::<f32>() - 0.5) * d.child_spread;
+                    let np = sp.wrap(a.p + jitter);
+                    // kids don't land in rock
+                    let np = if ground[sp.cell_of(np)].blocks() { a.p } else { np };
+                    let id = self.next_id;
+                    self.next_id = self.next_id.saturating_add(1);
+                    let g = a.g.mutate(rng, set.mutation);
+                    kids.push(Agent { id, p: np, pp: np, e: d.child_e * a.g.repro, age: 0, g, parent: a.id, born: now });
+                    lineage.born(id, a.id, s, now);
+                    if watched(s, a) {
+                        life.push(LifeEvent::Bred(id));
+                    }
+                    *born += 1;
+                }
             }
-        }
-
-        if self.preds.len() < 6 && rng.random::<f32>() < 0.20 {
-            for _ in 0..5 {
-                let p = vec2(rng.random::<f32>() * W as f32, rng.random::<f32>() * H as f32);
-                let id = self.next_id;
-                self.next_id = self.next_id.saturating_add(1);
-                self.preds.push(Agent { id, p, pp: p, e: 1.8, age: 0 });
-                pred_birth += 1;
+            list.extend(kids);
+        }
+
+        for (s, ((spec, list), born)) in self.species.iter().zip(self.pops.iter_mut()).zip(births.iter_mut()).enumerate() {
+            let d = &spec.def;
+            if (list.len() as u32) < d.respawn_below && rng.random::<f32>() < d.respawn_p {
+                for _ in 0..d.respawn_n {
+                    let Some(p) = terrain::open_pos(sp, ground, rng) else {
+                        continue;
+                    };
+                    let id = self.next_id;
+                    self.next_id = self.next_id.saturating_add(1);
+                    let g = Genome::BASE.mutate(rng, set.mutation);
+                    list.push(Agent { id, p, pp: p, e: d.respawn_e, age: 0, g, parent: 0, born: now });
+                    lineage.born(id, 0, s, now);
+                    *born += 1;
+                }
             }
         }
 
-        let herb_death = (herb_before - herb_after).saturating_add(eaten);
-        let pred_death = pred_before - pred_after;
-
-        Deltas { herb_birth, herb_death, pred_birth, pred_death }
+        Deltas { births, deaths }
     }
 }
 
-fn interp_agent(a: &Agent, alpha: f32) -> Vec2 {
-    let mut dx = a.p.x - a.pp.x;
-    let mut dy = a.p.y - a.pp.y;
+// positions of several species back to back, in the order given
+fn gather(pops: &[Vec<Agent>], which: &[usize]) -> Vec<Vec2> {
+    which.iter().flat_map(|&s| pops[s].iter().map(|a| a.p)).collect()
+}
 
-    if dx > (W as f32) * 0.5 { dx -= W as f32; }
-    if dx < -(W as f32) * 0.5 { dx += W as f32; }
-    if dy > (H as f32) * 0.5 { dy -= H as f32; }
-    if dy < -(H as f32) * 0.5 { dy += H as f32; }
+// what one species can see this phase
+struct Senses<'a> {
+    plants: Option<&'a [u8]>,
+    threats: Option<(&'a [Vec2], &'a SpatialGrid)>,
+    prey: Option<(&'a [Vec2], &'a SpatialGrid)>,
+    // sense radius multiplier, less than 1 in the dark
+    sight: f32,
+}
 
-    let x = wrap_f(a.pp.x + dx * alpha, W as f32);
-    let y = wrap_f(a.pp.y + dy * alpha, H as f32);
-    vec2(x, y)
+fn trait_stats(list: &[Agent]) -> TraitStats {
+    let mut out = TraitStats::default();
+    if list.is_empty() {
+        return out;
+    }
+    let n = list.len() as f64;
+    for (i, t) in out.iter_mut().enumerate() {
+        let mut s = 0.0f64;
+        let mut s2 = 0.0f64;
+        for a in list {
+            let v = a.g.get(i) as f64;
+            s += v;
+            s2 += v * v;
+        }
+        let mean = s / n;
+        t.mean = mean as f32;
+        t.sd = (s2 / n - mean * mean).max(0.0).sqrt() as f32;
+    }
+    out
 }
 
-fn herb_dir(p: Vec2, plants: &[u8], preds_pos: &[Vec2], rng: &mut impl Rng) -> Vec2 {
-    let base = best_plant_dir(p, plants);
-    let flee = flee_dir(p, preds_pos);
-    let mut d = base + flee * 1.15;
-    d += vec2(rng.random::<f32>() - 0.5, rng.random::<f32>() - 0.5) * 0.35;
-    norm_or_rand(d, rng)
+pub fn interp_agent(sp: Space, a: &Agent, alpha: f32) -> Vec2 {
+    let d = sp.delta(a.pp, a.p);
+    sp.wrap(a.pp + d * alpha)
 }
 
-fn pred_dir(p: Vec2, herb_pos: &[Vec2], rng: &mut impl Rng) -> Vec2 {
-    let chase = chase_dir(p, herb_pos);
-    let j = vec2(rng.random::<f32>() - 0.5, rng.random::<f32>() - 0.5) * 0.22;
-    norm_or_rand(chase + j, rng)
+fn agent_dir(sp: Space, p: Vec2, g: Genome, d: &SpeciesDef, look: &Senses, rng: &mut impl Rng) -> Vec2 {
+    let mut dir = match look.plants {
+        Some(plants) => best_plant_dir(sp, p, plants),
+        None => vec2(0.0, 0.0),
+    };
+    if let Some((pos, grid)) = look.threats {
+        dir += flee_dir(sp, p, d.flee_radius * g.sense * look.sight, pos, grid) * (d.flee_weight * g.drive);
+    }
+    if let Some((pos, grid)) = look.prey {
+        dir += chase_dir(sp, p, d.chase_radius * g.sense * look.sight, pos, grid) * g.drive;
+    }
+    dir += vec2(rng.random::<f32>() - 0.5, rng.random::<f32>() - 0.5) * d.wander;
+    norm_or_rand(dir, rng)
 }
 
-fn best_plant_dir(p: Vec2, plants: &[u8]) -> Vec2 {
-    let cx = wrap_i(p.x.floor() as i32, W);
-    let cy = wrap_i(p.y.floor() as i32, H);
+fn best_plant_dir(sp: Space, p: Vec2, plants: &[u8]) -> Vec2 {
+    let cx = p.x.floor() as i32;
+    let cy = p.y.floor() as i32;
 
     let mut best = -1i32;
     let mut bestv = vec2(0.0, 0.0);
@@ -486,9 +774,7 @@ fn best_plant_dir(p: Vec2, plants: &[u8]) -> Vec2 {
     for dy in -2..=2 {
         for dx in -2..=2 {
             if dx == 0 && dy == 0 { continue; }
-            let xx = wrap_i(cx + dx, W);
-            let yy = wrap_i(cy + dy, H);
-            let i = (yy * W + xx) as usize;
+            let Some(i) = sp.cell(cx + dx, cy + dy) else { continue };
             let v = plants[i] as i32 - (dx * dx + dy * dy) * 6;
             if v > best {
                 best = v;
@@ -500,69 +786,18 @@ fn best_plant_dir(p: Vec2, plants: &[u8]) -> Vec2 {
     bestv
 }
 
-fn flee_dir(p: Vec2, preds_pos: &[Vec2]) -> Vec2 {
-    if preds_pos.is_empty() { return vec2(0.0, 0.0); }
-
-    let mut best = vec2(0.0, 0.0);
-    let mut bestd = 9999.0;
-
-    for &pp in preds_pos {
-        let d = toroid_dist(p, pp);
-        if d < bestd {
-            bestd = d;
-            best = toroid_vec(pp, p);
-        }
-    }
-
-    if bestd < 8.0 { best } else { vec2(0.0, 0.0) }
-}
-
-fn chase_dir(p: Vec2, herb_pos: &[Vec2]) -> Vec2 {
-    if herb_pos.is_empty() { return vec2(0.0, 0.0); }
-
-    let mut best = vec2(0.0, 0.0);
-    let mut bestd = 9999.0;
-
-    for &hp in herb_pos {
-        let d = toroid_dist(p, hp);
-        if d < bestd {
-            bestd = d;
-            best = toroid_vec(p, hp);
-        }
+fn flee_dir(sp: Space, p: Vec2, r: f32, preds_pos: &[Vec2], grid: &SpatialGrid) -> Vec2 {
+    match grid.nearest(sp, preds_pos, p, r, |_| true) {
+        Some((i, _)) => sp.delta(preds_pos[i], p),
+        None => vec2(0.0, 0.0),
     }
-
-    if bestd < 18.0 { best } else { vec2(0.0, 0.0) }
 }
 
-fn nearest_within(p: Vec2, pts: &[Vec2], r: f32) -> Option<usize> {
-    let mut best = None;
-    let mut bestd = r;
-
-    for (i, &q) in pts.iter().enumerate() {
-        let d = toroid_dist(p, q);
-        if d < bestd {
-            bestd = d;
-            best = Some(i);
-        }
+fn chase_dir(sp: Space, p: Vec2, r: f32, herb_pos: &[Vec2], grid: &SpatialGrid) -> Vec2 {
+    match grid.nearest(sp, herb_pos, p, r, |_| true) {
+        Some((i, _)) => sp.delta(p, herb_pos[i]),
+        None => vec2(0.0, 0.0),
     }
-
-    best
-}
-
-fn toroid_vec(a: Vec2, b: Vec2) -> Vec2 {
-    let mut dx = b.x - a.x;
-    let mut dy = b.y - a.y;
-
-    if dx > (W as f32) * 0.5 { dx -= W as f32; }
-    if dx < -(W as f32) * 0.5 { dx += W as f32; }
-    if dy > (H as f32) * 0.5 { dy -= H as f32; }
-    if dy < -(H as f32) * 0.5 { dy += H as f32; }
-
-    vec2(dx, dy)
-}
-
-fn toroid_dist(a: Vec2, b: Vec2) -> f32 {
-    toroid_vec(a, b).length()
 }
 
 fn norm_or_rand(d: Vec2, rng: &mut impl Rng) -> Vec2 {
@@ -572,62 +807,3 @@ fn norm_or_rand(d: Vec2, rng: &mut impl Rng) -> Vec2 {
         vec2(a.cos(), a.sin())
     }
 }
-
-fn write_i32(w: &mut File, v: i32) -> std::io::Result<()> { w.write_all(&v.to_le_bytes()) }
-fn write_u32(w: &mut File, v: u32) -> std::io::Result<()> { w.write_all(&v.to_le_bytes()) }
-fn write_u8(w: &mut File, v: u8) -> std::io::Result<()> { w.write_all(&[v]) }
-fn write_f32(w: &mut File, v: f32) -> std::io::Result<()> { w.write_all(&v.to_le_bytes()) }
-
-fn read_i32(r: &mut File) -> std::io::Result<i32> { let mut b=[0u8;4]; r.read_exact(&mut b)?; Ok(i32::from_le_bytes(b)) }
-fn read_u32(r: &mut File) -> std::io::Result<u32> { let mut b=[0u8;4]; r.read_exact(&mut b)?; Ok(u32::from_le_bytes(b)) }
-fn read_u8(r: &mut File) -> std::io::Result<u8> { let mut b=[0u8;1]; r.read_exact(&mut b)?; Ok(b[0]) }
-fn read_f32(r: &mut File) -> std::io::Result<f32> { let mut b=[0u8;4]; r.read_exact(&mut b)?; Ok(f32::from_le_bytes(b)) }
-
-fn write_agent(w: &mut File, a: &Agent) -> std::io::Result<()> {
-    write_u32(w, a.id)?;
-    write_f32(w, a.p.x)?;
-    write_f32(w, a.p.y)?;
-    write_f32(w, a.pp.x)?;
-    write_f32(w, a.pp.y)?;
-    write_f32(w, a.e)?;
-    write_u32(w, a.age)?;
-    Ok(())
-}
-
-fn read_agent(r: &mut File) -> std::io::Result<Agent> {
-    let id = read_u32(r)?;
-    let px = read_f32(r)?;
-    let py = read_f32(r)?;
-    let ppx = read_f32(r)?;
-    let ppy = read_f32(r)?;
-    let e = read_f32(r)?;
-    let age = read_u32(r)?;
-    Ok(Agent { id, p: vec2(px, py), pp: vec2(ppx, ppy), e, age })
-}
-
-fn write_settings(w: &mut File, s: SimSettings) -> std::io::Result<()> {
-    write_u32(w, s.init_herbs)?;
-    write_u32(w, s.init_preds)?;
-    write_u8(w, s.plant_grow)?;
-    write_f32(w, s.plant_spread)?;
-    write_f32(w, s.herb_speed)?;
-    write_f32(w, s.pred_speed)?;
-    write_f32(w, s.herb_met)?;
-    write_f32(w, s.pred_met)?;
-    write_f32(w, s.eat_radius)?;
-    Ok(())
-}
-
-fn read_settings(r: &mut File) -> std::io::Result<SimSettings> {
-    Ok(SimSettings {
-        init_herbs: read_u32(r)?,
-        init_preds: read_u32(r)?,
-        plant_grow: read_u8(r)?,
-        plant_spread: read_f32(r)?,
-        herb_speed: read_f32(r)?,
-        pred_speed: read_f32(r)?,
-        herb_met: read_f32(r)?,
-        pred_met: read_f32(r)?,
-        eat_radius: read_f32(r)?,
-    })
-}
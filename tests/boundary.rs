@@ -0,0 +1,76 @@
+use ecosim::config::*;
+use ecosim::save::RunState;
+use ecosim::util::*;
+use ecosim::vec2::vec2;
+use ecosim::world::{Boundary, Space, World};
+
+#[test]
+fn walls_change_the_edge_math() {
+    let torus = Space::new(20, 10);
+    let reflect = torus.with_edge(Boundary::Reflect);
+    let closed = torus.with_edge(Boundary::Closed);
+
+    let out = vec2(21.5, -0.5);
+    assert_eq!(torus.wrap(out), vec2(1.5, 9.5));
+    assert_eq!(reflect.wrap(out), vec2(18.5, 0.5));
+    let c = closed.wrap(out);
+    assert!(c.x < 20.0 && c.x > 19.99 && c.y == 0.0);
+    assert_eq!(closed.cell_of(c), closed.idx(19, 0));
+
+    // no shortcut across the edge with walls
+    let (a, b) = (vec2(1.0, 1.0), vec2(19.0, 1.0));
+    assert_eq!(torus.dist(a, b), 2.0);
+    assert_eq!(reflect.dist(a, b), 18.0);
+
+    assert_eq!(torus.cell(-1, 0), Some(torus.idx(19, 0)));
+    assert_eq!(closed.cell(-1, 0), None);
+    assert_eq!(closed.cell(5, 10), None);
+    assert_eq!(closed.cell(19, 9), Some(199));
+
+    let set = SimSettings::from_text("boundary = \"reflect\"").unwrap();
+    assert_eq!(set.boundary, Boundary::Reflect);
+    assert_eq!(SimSettings::from_text(&set.to_text()).unwrap(), set);
+    let mut set = SimSettings::default();
+    set.set_value("boundary", "closed").unwrap();
+    assert_eq!(set.boundary, Boundary::Closed);
+    assert!(SimSettings::from_text("boundary = \"klein\"").is_err());
+}
+
+#[test]
+fn bounded_worlds_keep_everyone_inside() {
+    let dt = SimTuning::default().fixed_dt;
+    let mut runs = Vec::new();
+    for edge in Boundary::ALL {
+        let mut set = SimSettings { world_w: 48, world_h: 32, boundary: edge, ..SimSettings::default() };
+        set.species[0].init = 150;
+        set.species[1].init = 12;
+        let mut rng = rng_from_seed(8);
+        let mut world = World::new(&mut rng, &set);
+        let sp = world.space();
+        assert_eq!(sp.edge, edge);
+
+        let mut counts = Vec::new();
+        for _ in 0..400 {
+            world.step(&mut rng, &set, dt);
+            for s in 0..world.species().len() {
+                for a in world.agents(s) {
+                    assert!(a.p.x >= 0.0 && a.p.y >= 0.0 && a.p.x < 48.0 && a.p.y < 32.0, "{:?} outside with {:?}", a.p, edge);
+                }
+            }
+            counts.push(world.counts());
+        }
+
+        // the edge comes back with a save
+        let path = std::env::temp_dir().join(format!("ecosim_boundary_{}_{}.bin", edge.name(), std::process::id()));
+        let path = path.to_str().unwrap();
+        let run = RunState { seed: 8, steps: 400, speed: 1.0, rng: rng.clone() };
+        world.save(path, &set, &run).unwrap();
+        let back = World::load(path).unwrap();
+        std::fs::remove_file(path).ok();
+        assert_eq!(back.world.space(), sp);
+
+        runs.push(counts);
+    }
+    // same seed, different edges, different runs
+    assert!(runs[0] != runs[1] && runs[1] != runs[2]);
+}
@@ -0,0 +1,9 @@
+use ecosim::config::SimSettings;
+
+// a small world with a few of each, quick enough to step a few hundred times
+pub fn small_settings() -> SimSettings {
+    let mut set = SimSettings { world_w: 60, world_h: 40, ..SimSettings::default() };
+    set.species[0].init = 200;
+    set.species[1].init = 12;
+    set
+}
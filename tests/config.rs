@@ -0,0 +1,31 @@
+use ecosim::config::*;
+
+#[test]
+fn settings_roundtrip_through_text() {
+    let mut set = SimSettings::from_text("spread_from = 90\nspread_into = 70\nspread_amount = 200\nmutation = 0.1").unwrap();
+    set.species[1].kill_e = 1.25;
+    assert_eq!((set.spread_from, set.spread_into, set.spread_amount), (90, 70, 200));
+    assert_eq!(SimSettings::from_text(&set.to_text()).unwrap(), set);
+    assert_eq!(SimSettings::from_text("").unwrap(), SimSettings::default());
+}
+
+#[test]
+fn out_of_range_settings_are_rejected() {
+    for text in [
+        "plant_spread = 1.5",
+        "eat_radius = -1",
+        "mutation = nan",
+        "spread_amount = 300",
+        "[species.a]\nrepro_p = 2",
+        "[species.a]\nspeed = -0.1",
+    ] {
+        assert!(SimSettings::from_text(text).is_err(), "accepted {:?}", text);
+    }
+}
+
+#[test]
+fn lenient_parsing_skips_unknown_keys() {
+    let text = "from_the_future = 1\nworld_w = 200\n[extra]\nx = 1";
+    assert!(SimSettings::from_text(text).is_err());
+    assert_eq!(SimSettings::from_text_lenient(text).unwrap().world_w, 200);
+}
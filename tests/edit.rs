@@ -0,0 +1,78 @@
+use ecosim::config::*;
+use ecosim::edit::{self, Edit};
+use ecosim::replay::{Event, Playback, Replay};
+use ecosim::util::*;
+use ecosim::vec2::vec2;
+use ecosim::world::{LifeEvent, TrackTarget, World};
+
+mod common;
+use common::small_settings;
+
+#[test]
+fn edits_do_what_they_say() {
+    let set = small_settings();
+    let mut rng = rng_from_seed(2);
+    let mut world = World::new(&mut rng, &set);
+
+    // painting wraps across the corner and clamps at 0
+    edit::apply(&mut world, Edit::Plants { at: vec2(0.2, 0.2), r: 1.5, amount: -255 });
+    let sp = world.space();
+    for (x, y) in [(0, 0), (59, 0), (0, 39), (59, 39)] {
+        assert_eq!(world.plants()[sp.idx(x, y)], 0);
+    }
+
+    let n = world.agents(1).len();
+    edit::apply(&mut world, Edit::Spawn { species: 1, at: vec2(30.0, 20.0), e: 5.0 });
+    let a = *world.agents(1).last().unwrap();
+    assert_eq!(world.agents(1).len(), n + 1);
+    assert_eq!((a.e, a.parent), (5.0, 0));
+    assert!(world.lineage().get(a.id).is_some());
+
+    // out of range species does nothing
+    edit::apply(&mut world, Edit::Spawn { species: 9, at: vec2(30.0, 20.0), e: 5.0 });
+    assert_eq!(world.agents(1).len(), n + 1);
+
+    world.watch(Some(TrackTarget { species: 1, id: a.id }));
+    edit::apply(&mut world, Edit::Remove { at: vec2(30.0, 20.0), r: 0.5 });
+    assert!(world.tracked_info(TrackTarget { species: 1, id: a.id }).is_none());
+    assert_eq!(world.lineage().get(a.id).unwrap().died, Some(0));
+    assert_eq!(world.take_life_events(), vec![LifeEvent::Removed]);
+}
+
+#[test]
+fn playback_applies_edits_at_their_step() {
+    let set = small_settings();
+    let dt = SimTuning::default().fixed_dt;
+    let edits = [
+        (10, Edit::Plants { at: vec2(12.0, 12.0), r: 6.0, amount: 120 }),
+        (25, Edit::Spawn { species: 1, at: vec2(12.0, 12.0), e: 8.0 }),
+        (40, Edit::Remove { at: vec2(40.0, 20.0), r: 8.0 }),
+    ];
+
+    let mut rng = rng_from_seed(17);
+    let mut world = World::new(&mut rng, &set);
+    let mut expected = Vec::new();
+    for t in 0..80 {
+        for &(at, ed) in &edits {
+            if at == t {
+                edit::apply(&mut world, ed);
+            }
+        }
+        let d = world.step(&mut rng, &set, dt);
+        expected.push((world.counts(), d));
+    }
+
+    let mut r = Replay::new(17, &set);
+    for (i, &(at, ed)) in edits.iter().enumerate() {
+        r.push(at, i as u64 * 100, Event::Edit(ed));
+    }
+    r.end = 80;
+
+    let mut play = Playback::new(&r);
+    let mut got = Vec::new();
+    while !play.done() {
+        let d = play.step(dt);
+        got.push((play.world.counts(), d));
+    }
+    assert!(got == expected);
+}
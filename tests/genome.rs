@@ -0,0 +1,24 @@
+use ecosim::genome::{Genome, TRAIT_NAMES};
+use ecosim::util::*;
+
+#[test]
+fn base_genome_costs_the_baseline() {
+    assert_eq!(Genome::BASE.upkeep(), 1.0);
+    // faster has to cost more, or speed would just run away
+    let fast = Genome { speed: 1.5, ..Genome::BASE };
+    assert!(fast.upkeep() > 1.0);
+}
+
+#[test]
+fn mutation_stays_in_range() {
+    let mut rng = rng_from_seed(9);
+    assert_eq!(Genome::BASE.mutate(&mut rng, 0.0), Genome::BASE);
+
+    let mut g = Genome::BASE;
+    for _ in 0..5000 {
+        g = g.mutate(&mut rng, 0.5);
+        for (i, name) in TRAIT_NAMES.iter().enumerate() {
+            assert!((0.25..=4.0).contains(&g.get(i)), "{} = {}", name, g.get(i));
+        }
+    }
+}
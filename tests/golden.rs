@@ -0,0 +1,93 @@
+use ecosim::config::*;
+use ecosim::stats::{Counts, Deltas};
+use ecosim::util::*;
+use ecosim::world::World;
+
+// fnv-1a over everything counts() and step() report, chained step to step
+struct Fnv(u64);
+
+impl Fnv {
+    fn bytes(&mut self, b: &[u8]) {
+        for &x in b {
+            self.0 = (self.0 ^ x as u64).wrapping_mul(0x0000_0100_0000_01b3);
+        }
+    }
+
+    fn step(&mut self, c: &Counts, d: &Deltas) {
+        self.bytes(&c.plants_avg.to_bits().to_le_bytes());
+        for p in &c.pops {
+            self.bytes(&p.n.to_le_bytes());
+            self.bytes(&p.e_avg.to_bits().to_le_bytes());
+            for t in &p.traits {
+                self.bytes(&t.mean.to_bits().to_le_bytes());
+                self.bytes(&t.sd.to_bits().to_le_bytes());
+            }
+        }
+        for (b, dd) in d.births.iter().zip(&d.deaths) {
+            self.bytes(&b.to_le_bytes());
+            self.bytes(&dd.to_le_bytes());
+        }
+    }
+}
+
+// default settings, one thread (any thread count gives the same run). returns the hash at each checkpoint and
+// the predator count after every step
+fn run(seed: u64, steps: u64, checkpoints: &[u64]) -> (Vec<u64>, Vec<u32>) {
+    let set = SimSettings { threads: 1, ..SimSettings::default() };
+    let dt = SimTuning::default().fixed_dt;
+    let mut rng = rng_from_seed(seed);
+    let mut world = World::new(&mut rng, &set);
+
+    let mut h = Fnv(0xcbf2_9ce4_8422_2325);
+    let mut hashes = Vec::new();
+    let mut preds = Vec::new();
+    for s in 1..=steps {
+        let d = world.step(&mut rng, &set, dt);
+        let c = world.counts();
+        h.step(&c, &d);
+        preds.push(c.pops[1].n);
+        if checkpoints.contains(&s) {
+            hashes.push(h.0);
+        }
+    }
+    (hashes, preds)
+}
+
+const CHECKPOINTS: [u64; 5] = [1, 100, 500, 1000, 2500];
+
+// preds in steps from..to (1-based, inclusive)
+fn window(preds: &[u32], from: usize, to: usize) -> &[u32] {
+    &preds[from - 1..to]
+}
+
+#[test]
+fn seed_123456789_predators_collapse_after_about_2k_steps() {
+    let (hashes, preds) = run(123456789, 2500, &CHECKPOINTS);
+    assert_eq!(
+        hashes,
+        [0xbda46341bf9392a7, 0x097b96af95b0ae45, 0xc4b0f03cda267447, 0xc067fadd2d5d0054, 0x3cb98107eee898fb],
+        "dynamics changed, if that's on purpose update the hashes (and examples/seeds/seeds.md if the outcomes moved)"
+    );
+
+    // holding on through a first cycle, then down to respawn levels
+    assert!(window(&preds, 200, 2000).iter().all(|&n| n > 20));
+    assert!(window(&preds, 2000, 2500).iter().any(|&n| n <= 10));
+}
+
+#[test]
+fn seed_987654321_oscillates_without_collapse() {
+    let (hashes, preds) = run(987654321, 2500, &CHECKPOINTS);
+    assert_eq!(
+        hashes,
+        [0x58558ae8beffcefa, 0x5851048ade8cf229, 0xd96508d505d08cf7, 0xa7defc358bcbe630, 0x3245e913398fa7de],
+        "dynamics changed, if that's on purpose update the hashes (and examples/seeds/seeds.md if the outcomes moved)"
+    );
+
+    // boom, bust, boom again, never near respawn levels
+    assert!(window(&preds, 200, 2500).iter().all(|&n| n > 20));
+    let peak = (1..=1200).max_by_key(|&s| preds[s - 1]).unwrap();
+    assert!(preds[peak - 1] > 400);
+    let trough = (peak..=2200).min_by_key(|&s| preds[s - 1]).unwrap();
+    assert!(preds[trough - 1] < 100);
+    assert!(window(&preds, trough, 2500).iter().any(|&n| n > 200));
+}
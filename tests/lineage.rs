@@ -0,0 +1,64 @@
+use ecosim::config::*;
+use ecosim::lineage::Lineage;
+use ecosim::save::RunState;
+use ecosim::util::*;
+use ecosim::world::World;
+
+mod common;
+use common::small_settings;
+
+#[test]
+fn prune_keeps_the_ancestry_of_the_living() {
+    // 1 -> 2 -> 4, 1 -> 3, 5 alone
+    let mut l = Lineage::new();
+    l.born(1, 0, 0, 0);
+    l.born(2, 1, 0, 10);
+    l.born(3, 1, 0, 12);
+    l.born(4, 2, 0, 20);
+    l.born(5, 0, 0, 0);
+    for id in [1, 2, 3, 5] {
+        l.died(id, 30);
+    }
+
+    assert_eq!(l.descendants(1), vec![2, 4, 3]);
+    let mut kept = l.clone();
+    kept.prune(Some(3));
+    assert_eq!(kept.len(), 4);
+
+    l.prune(None);
+    let ids: Vec<u32> = l.records().map(|(id, _)| id).collect();
+    assert_eq!(ids, vec![1, 2, 4]);
+    assert_eq!(l.get(1).unwrap().kids, vec![2]);
+}
+
+#[test]
+fn births_are_recorded_and_survive_a_save() {
+    let set = small_settings();
+    let dt = SimTuning::default().fixed_dt;
+    let mut rng = rng_from_seed(4);
+    let mut world = World::new(&mut rng, &set);
+    for _ in 0..400 {
+        world.step(&mut rng, &set, dt);
+    }
+    assert_eq!(world.now(), 400);
+
+    // every kid points at a parent the store knows, born when its age says
+    let kids: Vec<_> = world.agents(0).iter().filter(|a| a.parent != 0).collect();
+    assert!(!kids.is_empty());
+    for a in &kids {
+        assert_eq!(a.born + a.age as u64, world.now());
+        assert!(world.lineage().get(a.parent).is_some_and(|p| p.kids.contains(&a.id)));
+    }
+
+    let path = std::env::temp_dir().join(format!("ecosim_lineage_{}.bin", std::process::id()));
+    let path = path.to_str().unwrap();
+    let run = RunState { seed: 4, steps: 400, speed: 1.0, rng: rng.clone() };
+    world.save(path, &set, &run).unwrap();
+    let back = World::load(path).unwrap().world;
+    std::fs::remove_file(path).ok();
+
+    assert_eq!(back.now(), 400);
+    assert!(back.lineage() == world.lineage());
+    let parents = |w: &World| w.agents(0).iter().map(|a| (a.id, a.parent, a.born)).collect::<Vec<_>>();
+    assert_eq!(parents(&back), parents(&world));
+}
@@ -0,0 +1,87 @@
+use ecosim::config::*;
+use ecosim::edit::Edit;
+use ecosim::replay::{Event, Playback, Replay};
+use ecosim::util::*;
+use ecosim::vec2::vec2;
+use ecosim::world::{TrackTarget, World};
+
+mod common;
+use common::small_settings;
+
+#[test]
+fn replay_roundtrips_through_a_file() {
+    let mut r = Replay::new(42, &small_settings());
+    r.push(0, 0, Event::Speed(2.0));
+    r.push(10, 500, Event::Pause);
+    r.push(10, 1500, Event::Resume);
+    r.push(12, 1600, Event::Track(Some(TrackTarget { species: 1, id: 77 })));
+    r.push(20, 2000, Event::Restart);
+    r.push(20, 2000, Event::Track(None));
+    r.push(25, 2100, Event::NewSeed(9));
+    r.push(27, 2200, Event::Reseed(13));
+    r.push(28, 2300, Event::Edit(Edit::Plants { at: vec2(3.5, 4.0), r: 2.5, amount: -24 }));
+    r.push(28, 2310, Event::Edit(Edit::Spawn { species: 1, at: vec2(10.0, 11.0), e: 3.5 }));
+    r.push(29, 2400, Event::Edit(Edit::Remove { at: vec2(20.0, 5.0), r: 4.0 }));
+    r.end = 30;
+
+    let path = std::env::temp_dir().join(format!("ecosim_replay_{}.replay", std::process::id()));
+    let path = path.to_str().unwrap();
+    r.write(path).unwrap();
+    let back = Replay::read(path).unwrap();
+    std::fs::remove_file(path).ok();
+
+    assert_eq!(back, r);
+}
+
+#[test]
+fn tracking_a_missing_species_is_rejected() {
+    let mut r = Replay::new(42, &small_settings());
+    r.push(3, 0, Event::Track(Some(TrackTarget { species: 2, id: 1 })));
+    r.end = 10;
+
+    let path = std::env::temp_dir().join(format!("ecosim_replay_track_{}.replay", std::process::id()));
+    let path = path.to_str().unwrap();
+    r.write(path).unwrap();
+    let back = Replay::read(path);
+    std::fs::remove_file(path).ok();
+    assert!(back.is_err());
+}
+
+#[test]
+fn playback_matches_the_run_it_recorded() {
+    let set = small_settings();
+    let dt = SimTuning::default().fixed_dt;
+
+    // what the gui does: step, R at 40, N with seed 5 at 70, stop at 100
+    let mut rng = rng_from_seed(11);
+    let mut world = World::new(&mut rng, &set);
+    let mut expected = Vec::new();
+    for t in 0..100 {
+        if t == 40 {
+            world = World::new(&mut rng, &set);
+        }
+        if t == 70 {
+            rng = rng_from_seed(5);
+            world = World::new(&mut rng, &set);
+        }
+        let d = world.step(&mut rng, &set, dt);
+        expected.push((world.counts(), d));
+    }
+
+    let mut r = Replay::new(11, &set);
+    r.push(15, 100, Event::Pause);
+    r.push(15, 900, Event::Resume);
+    r.push(40, 1000, Event::Restart);
+    r.push(70, 2000, Event::NewSeed(5));
+    r.end = 100;
+
+    let mut play = Playback::new(&r);
+    let mut got = Vec::new();
+    while !play.done() {
+        let d = play.step(dt);
+        got.push((play.world.counts(), d));
+    }
+
+    assert_eq!(play.seed, 5);
+    assert!(got == expected);
+}
@@ -0,0 +1,106 @@
+use ecosim::config::*;
+use ecosim::edit::Edit;
+use ecosim::replay::{Event, Playback, Replay};
+use ecosim::rewind::Rewind;
+use ecosim::util::*;
+use ecosim::vec2::vec2;
+use ecosim::world::World;
+
+mod common;
+use common::small_settings;
+
+#[test]
+fn rewinding_and_stepping_on_gives_the_same_run() {
+    let set = small_settings();
+    let dt = SimTuning::default().fixed_dt;
+
+    let mut rng = rng_from_seed(21);
+    let mut world = World::new(&mut rng, &set);
+    let mut rw = Rewind::new(50, 100);
+    rw.record(0, 0, 21, &world, &rng, 0);
+
+    let mut expected = Vec::new();
+    for s in 1..=300u64 {
+        let d = world.step(&mut rng, &set, dt);
+        rw.record(s, s, 21, &world, &rng, 0);
+        expected.push((world.counts(), d));
+    }
+    assert_eq!(rw.snaps().len(), 7);
+
+    // back to step 100, the snapshots after it go
+    let snap = rw.rewind(2).unwrap();
+    assert_eq!(snap.steps, 100);
+    let mut world = snap.world(&world);
+    let mut rng = snap.rng.clone();
+    assert_eq!(rw.snaps().len(), 3);
+
+    for s in 101..=300u64 {
+        let d = world.step(&mut rng, &set, dt);
+        rw.record(s, s, 21, &world, &rng, 0);
+        assert!((world.counts(), d) == expected[s as usize - 1], "diverged at step {}", s);
+    }
+    assert_eq!(rw.snaps().len(), 7);
+}
+
+#[test]
+fn ring_keeps_only_the_newest() {
+    let set = small_settings();
+    let mut rng = rng_from_seed(3);
+    let world = World::new(&mut rng, &set);
+
+    let mut rw = Rewind::new(10, 4);
+    for s in 0..=95u64 {
+        rw.record(s, s, 3, &world, &rng, 0);
+    }
+    let kept: Vec<u64> = rw.snaps().iter().map(|s| s.steps).collect();
+    assert_eq!(kept, vec![60, 70, 80, 90]);
+
+    // same step twice is one snapshot
+    rw.record(90, 90, 3, &world, &rng, 0);
+    assert_eq!(rw.snaps().len(), 4);
+    assert!(rw.rewind(4).is_none());
+}
+
+// plants plus who is where
+fn fingerprint(w: &World) -> (Vec<u8>, Vec<(u32, f32, f32)>) {
+    let agents = (0..w.species().len()).flat_map(|s| w.agents(s).iter().map(|a| (a.id, a.p.x, a.p.y))).collect();
+    (w.plants().to_vec(), agents)
+}
+
+#[test]
+fn rewinding_drops_edits_made_after_the_snapshot() {
+    let set = small_settings();
+    let dt = SimTuning::default().fixed_dt;
+
+    let mut rng = rng_from_seed(21);
+    let mut world = World::new(&mut rng, &set);
+    let mut log = Replay::new(21, &set);
+    let mut rw = Rewind::new(10, 100);
+    rw.record(0, 0, 21, &world, &rng, log.events.len());
+    for s in 1..=50u64 {
+        world.step(&mut rng, &set, dt);
+        rw.record(s, s, 21, &world, &rng, log.events.len());
+    }
+
+    // paused on step 50, after its snapshot: an edit, then back to 50
+    let ed = Edit::Remove { at: vec2(30.0, 20.0), r: 15.0 };
+    ecosim::edit::apply(&mut world, ed);
+    log.push(50, 0, Event::Edit(ed));
+    let snap = rw.rewind(5).unwrap();
+    assert_eq!(snap.steps, 50);
+    let mut world = snap.world(&world);
+    let mut rng = snap.rng.clone();
+    log.truncate(snap.tick, snap.events);
+    assert!(log.events.is_empty());
+
+    for _ in 51..=80u64 {
+        world.step(&mut rng, &set, dt);
+    }
+    log.end = 80;
+
+    let mut play = Playback::new(&log);
+    while !play.done() {
+        play.step(dt);
+    }
+    assert!(fingerprint(&play.world) == fingerprint(&world));
+}
@@ -0,0 +1,111 @@
+use ecosim::config::*;
+use ecosim::save::{RunState, SaveError};
+use ecosim::util::*;
+use ecosim::world::World;
+
+fn tmp_path(name: &str) -> String {
+    let p = std::env::temp_dir().join(format!("ecosim_{}_{}.bin", name, std::process::id()));
+    p.to_str().unwrap().to_string()
+}
+
+fn small_world(seed: u64) -> (World, SimSettings, SimRng) {
+    let mut set = SimSettings::default();
+    set.species[0].init = 120;
+    set.species[1].init = 10;
+    let mut rng = rng_from_seed(seed);
+    let world = World::new(&mut rng, &set);
+    (world, set, rng)
+}
+
+#[test]
+fn unknown_sections_are_skipped() {
+    let (world, mut set, rng) = small_world(7);
+    set.soil_start = 90;
+    let path = tmp_path("unknown");
+    let run = RunState { seed: 7, steps: 0, speed: 1.0, rng };
+    world.save(&path, &set, &run).unwrap();
+
+    // splice a section from the future in front of END
+    let mut buf = std::fs::read(&path).unwrap();
+    let end = buf.len() - 8;
+    assert_eq!(&buf[end..end + 4], b"END ");
+    let mut extra = b"XTRA".to_vec();
+    extra.extend_from_slice(&5u32.to_le_bytes());
+    extra.extend_from_slice(b"hello");
+    buf.splice(end..end, extra);
+    // and hide the soil, so it comes back as soil_start from the settings
+    let soil = buf.windows(4).position(|w| w == b"SOIL").unwrap();
+    buf[soil..soil + 4].copy_from_slice(b"SOIX");
+    std::fs::write(&path, &buf).unwrap();
+
+    let data = World::load(&path).unwrap();
+    std::fs::remove_file(&path).ok();
+
+    assert!(!data.upgraded);
+    assert_eq!(data.world.counts(), world.counts());
+    assert_eq!(data.set.species[0].init, 120);
+    assert!(data.world.soil().iter().all(|&v| v == 90));
+}
+
+#[test]
+fn eco3_files_are_upgraded() {
+    // eco3 only ever had the old fixed world size
+    const W: i32 = 120;
+    const H: i32 = 80;
+
+    let mut buf = b"ECO3".to_vec();
+    buf.extend_from_slice(&W.to_le_bytes());
+    buf.extend_from_slice(&H.to_le_bytes());
+
+    let set = SimSettings::default();
+    let (herb, pred) = (&set.species[0], &set.species[1]);
+    buf.extend_from_slice(&herb.init.to_le_bytes());
+    buf.extend_from_slice(&pred.init.to_le_bytes());
+    buf.push(set.plant_grow);
+    for v in [set.plant_spread, herb.speed, pred.speed, herb.met, pred.met, set.eat_radius] {
+        buf.extend_from_slice(&v.to_le_bytes());
+    }
+
+    buf.extend_from_slice(&3u32.to_le_bytes());
+    buf.extend_from_slice(&((W * H) as u32).to_le_bytes());
+    buf.extend(std::iter::repeat_n(128u8, (W * H) as usize));
+
+    // one herb, one pred
+    for _ in 0..2 {
+        buf.extend_from_slice(&1u32.to_le_bytes());
+        buf.extend_from_slice(&1u32.to_le_bytes());
+        for v in [4.0f32, 5.0, 4.0, 5.0, 1.5] {
+            buf.extend_from_slice(&v.to_le_bytes());
+        }
+        buf.extend_from_slice(&9u32.to_le_bytes());
+    }
+
+    let path = tmp_path("eco3");
+    std::fs::write(&path, &buf).unwrap();
+    let data = World::load(&path).unwrap();
+    std::fs::remove_file(&path).ok();
+
+    assert!(data.upgraded);
+    assert!(data.run.is_none());
+    assert_eq!((data.set.world_w, data.set.world_h), (W, H));
+    let c = data.world.counts();
+    assert_eq!((c.pops[0].n, c.pops[1].n), (1, 1));
+    assert_eq!(c.pops[0].e_avg, 1.5);
+    assert_eq!(data.world.species_names(), ["herb", "pred"]);
+    assert!(data.world.soil().iter().all(|&v| v == data.set.soil_start));
+}
+
+#[test]
+fn truncated_saves_are_rejected() {
+    let (world, set, rng) = small_world(8);
+    let path = tmp_path("trunc");
+    let run = RunState { seed: 8, steps: 0, speed: 1.0, rng };
+    world.save(&path, &set, &run).unwrap();
+
+    let buf = std::fs::read(&path).unwrap();
+    std::fs::write(&path, &buf[..buf.len() / 2]).unwrap();
+    let err = World::load(&path).err().unwrap();
+    std::fs::remove_file(&path).ok();
+
+    assert!(matches!(err, SaveError::Truncated));
+}
@@ -0,0 +1,52 @@
+use ecosim::config::*;
+use ecosim::stats::{Counts, Deltas};
+use ecosim::util::*;
+use ecosim::save::RunState;
+use ecosim::world::World;
+
+mod common;
+use common::small_settings;
+
+fn run(world: &mut World, rng: &mut SimRng, set: &SimSettings, n: u64) -> Vec<(Counts, Deltas)> {
+    let dt = SimTuning::default().fixed_dt;
+    (0..n)
+        .map(|_| {
+            let d = world.step(rng, set, dt);
+            (world.counts(), d)
+        })
+        .collect()
+}
+
+#[test]
+fn save_load_continue_matches_uninterrupted_run() {
+    let seed = 987_654_321;
+    let set = small_settings();
+    let path = std::env::temp_dir().join(format!("ecosim_resume_{}.bin", std::process::id()));
+    let path = path.to_str().unwrap();
+
+    let mut rng = rng_from_seed(seed);
+    let mut world = World::new(&mut rng, &set);
+    run(&mut world, &mut rng, &set, 120);
+
+    let run_state = RunState {
+        seed,
+        steps: 120,
+        speed: 2.0,
+        rng: rng.clone(),
+    };
+    world.save(path, &set, &run_state).expect("save failed");
+
+    let expected = run(&mut world, &mut rng, &set, 180);
+
+    let data = World::load(path).expect("load failed");
+    std::fs::remove_file(path).ok();
+
+    let (mut loaded, set2) = (data.world, data.set);
+    let mut run2 = data.run.expect("run state missing from save");
+    assert_eq!(run2.seed, seed);
+    assert_eq!(run2.steps, 120);
+    assert_eq!(run2.speed, 2.0);
+
+    let got = run(&mut loaded, &mut run2.rng, &set2, 180);
+    assert_eq!(got, expected);
+}
@@ -0,0 +1,60 @@
+use ecosim::config::*;
+use ecosim::seasons::{self, at_night, growth, light, season, season_name};
+use ecosim::util::*;
+use ecosim::world::World;
+
+#[test]
+fn cycles_follow_the_step() {
+    let mut set = SimSettings::default();
+    assert_eq!((growth(&set, 123), light(&set, 123), season(&set, 123)), (1.0, 1.0, None));
+
+    set.season_len = 400;
+    set.season_amp = 0.5;
+    // summer peaks a quarter of the way in, winter bottoms out at three
+    assert!((growth(&set, 100) - 1.5).abs() < 1e-5);
+    assert!((growth(&set, 300) - 0.5).abs() < 1e-5);
+    assert!((growth(&set, 400) - 1.0).abs() < 1e-5);
+    let names: Vec<String> = [0, 100, 200, 300, 399].iter().map(|&s| season_name(&set, season(&set, s).unwrap())).collect();
+    assert_eq!(names, ["spring", "summer", "autumn", "winter", "spring"]);
+
+    set.season_grow = vec![2.0, 0.0, 1.0];
+    assert_eq!([growth(&set, 0), growth(&set, 140), growth(&set, 399), growth(&set, 400)], [2.0, 0.0, 1.0, 2.0]);
+    assert_eq!(season_name(&set, 1), "season 2");
+
+    set.day_len = 100;
+    assert!((light(&set, 0) - 0.5).abs() < 1e-5);
+    assert!((light(&set, 25) - 1.0).abs() < 1e-5);
+    assert!(light(&set, 75) < 1e-5 && seasons::is_night(light(&set, 75)));
+    assert_eq!(at_night(0.5, 1.0), 1.0);
+    assert!((at_night(0.5, 0.0) - 0.5).abs() < 1e-6);
+
+    set.species[1].night_sight = 0.25;
+    assert_eq!(SimSettings::from_text(&set.to_text()).unwrap(), set);
+    assert!(SimSettings::from_text("season_amp = 2").is_err());
+    assert!(SimSettings::from_text("season_grow = [1, -1]").is_err());
+}
+
+#[test]
+fn grazers_stop_in_the_dark() {
+    let mut set = SimSettings { world_w: 60, world_h: 40, day_len: 8, ..SimSettings::default() };
+    set.species[0].init = 200;
+    set.species[0].night_speed = 0.0;
+    set.species[1].init = 0;
+    set.species[1].respawn_p = 0.0;
+    let dt = SimTuning::default().fixed_dt;
+
+    let mut rng = rng_from_seed(3);
+    let mut world = World::new(&mut rng, &set);
+    let sp = world.space();
+    let moved = |w: &World| w.agents(0).iter().map(|a| sp.dist(a.pp, a.p)).fold(0.0f32, f32::max);
+    for _ in 0..24 {
+        world.step(&mut rng, &set, dt);
+        match world.now() % 8 {
+            // midnight
+            6 => assert!(moved(&world) < 1e-3, "moved {} at night", moved(&world)),
+            // noon
+            2 => assert!(moved(&world) > 0.1),
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,78 @@
+use ecosim::config::*;
+use ecosim::save::RunState;
+use ecosim::soil;
+use ecosim::util::*;
+use ecosim::vec2::vec2;
+use ecosim::world::{Space, World};
+
+#[test]
+fn nutrients_scale_growth() {
+    assert_eq!([soil::growth(5, soil::MID), soil::growth(5, 0), soil::growth(5, 255)], [5, 0, 9]);
+    assert_eq!([soil::drain(16, 0.1), soil::drain(16, 0.0), soil::drain(200, 16.0)], [2, 0, 255]);
+
+    let sp = Space::new(4, 4);
+    let mut cells = vec![100u8; sp.cells()];
+    soil::deposit(&mut cells, sp, vec2(2.5, 1.5), 200);
+    assert_eq!(cells[sp.idx(2, 1)], 255);
+    cells[0] = 0;
+    soil::drift(&mut cells, 100);
+    assert_eq!((cells[0], cells[sp.idx(2, 1)], cells[1]), (1, 254, 100));
+
+    let set = SimSettings { soil: true, soil_start: 90, soil_drift: 0, ..SimSettings::default() };
+    assert_eq!(SimSettings::from_text(&set.to_text()).unwrap(), set);
+    assert!(SimSettings::from_text("soil = yes").is_err());
+    assert!(SimSettings::from_text("soil_graze = -1").is_err());
+}
+
+#[test]
+fn grazing_drains_and_deaths_feed() {
+    let dt = SimTuning::default().fixed_dt;
+    let mut set = SimSettings { world_w: 40, world_h: 30, soil_drift: 0, ..SimSettings::default() };
+    set.species[0].init = 120;
+    set.species[0].respawn_p = 0.0;
+    set.species[1].init = 0;
+    set.species[1].respawn_p = 0.0;
+    let start = set.soil_start as u64 * 1200;
+    let total = |w: &World| w.soil().iter().map(|&v| v as u64).sum::<u64>();
+
+    // off, nothing touches it
+    let mut rng = rng_from_seed(4);
+    let mut world = World::new(&mut rng, &set);
+    for _ in 0..50 {
+        world.step(&mut rng, &set, dt);
+    }
+    assert_eq!(total(&world), start);
+
+    // grazing only takes
+    set.soil = true;
+    set.soil_graze = 1.0;
+    set.soil_corpse = 0;
+    let mut rng = rng_from_seed(4);
+    let mut world = World::new(&mut rng, &set);
+    for _ in 0..50 {
+        world.step(&mut rng, &set, dt);
+    }
+    assert!(total(&world) < start);
+
+    // everyone starves, each one gives soil_corpse back where it fell
+    set.soil_graze = 0.0;
+    set.soil_corpse = 10;
+    set.species[0].met = 1.0;
+    let mut rng = rng_from_seed(4);
+    let mut world = World::new(&mut rng, &set);
+    let n = world.agents(0).len() as u64;
+    for _ in 0..100 {
+        world.step(&mut rng, &set, dt);
+    }
+    assert!(world.agents(0).is_empty());
+    assert_eq!(total(&world), start + 10 * n);
+
+    // and it comes back with a save
+    let path = std::env::temp_dir().join(format!("ecosim_soil_{}.bin", std::process::id()));
+    let path = path.to_str().unwrap();
+    let run = RunState { seed: 4, steps: 100, speed: 1.0, rng: rng.clone() };
+    world.save(path, &set, &run).unwrap();
+    let back = World::load(path).unwrap();
+    std::fs::remove_file(path).ok();
+    assert_eq!(back.world.soil(), world.soil());
+}
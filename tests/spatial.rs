@@ -0,0 +1,47 @@
+use ecosim::spatial::SpatialGrid;
+use ecosim::util::*;
+use ecosim::vec2::Vec2;
+use ecosim::world::{Boundary, Space};
+
+// what the grid replaced: front to back scan, strict <, first hit wins ties
+fn nearest_linear(sp: Space, pts: &[Vec2], p: Vec2, r: f32, keep: impl Fn(usize) -> bool) -> Option<(usize, f32)> {
+    let mut best: Option<(usize, f32)> = None;
+    for (i, &q) in pts.iter().enumerate() {
+        let d = sp.dist(p, q);
+        if d < r && keep(i) && best.is_none_or(|(_, bd)| d < bd) {
+            best = Some((i, d));
+        }
+    }
+    best
+}
+
+#[test]
+fn grid_matches_linear_scan() {
+    let mut rng = rng_from_seed(42);
+
+    for (&(w, h, n), edge) in [(120, 80, 900), (17, 33, 40), (16, 16, 3), (300, 200, 2000)].iter().zip(Boundary::ALL.iter().cycle()) {
+        let sp = Space::new(w, h).with_edge(*edge);
+        let mut pts: Vec<Vec2> = (0..n).map(|_| sp.random_pos(&mut rng)).collect();
+        // duplicates so ties actually happen
+        pts.extend_from_within(..n / 10);
+
+        let mut grid = SpatialGrid::default();
+        grid.rebuild(sp, &pts);
+
+        for k in 0..400 {
+            let p = sp.random_pos(&mut rng);
+            for &r in &[0.75, 8.0, 18.0, 90.0] {
+                let keep = |i: usize| !(i + k).is_multiple_of(3);
+                assert_eq!(grid.nearest(sp, &pts, p, r, keep), nearest_linear(sp, &pts, p, r, keep));
+            }
+        }
+    }
+}
+
+#[test]
+fn empty_grid_finds_nothing() {
+    let sp = Space::new(120, 80);
+    let mut grid = SpatialGrid::default();
+    grid.rebuild(sp, &[]);
+    assert_eq!(grid.nearest(sp, &[], Vec2 { x: 3.0, y: 4.0 }, 18.0, |_| true), None);
+}
@@ -0,0 +1,88 @@
+use ecosim::config::*;
+use ecosim::species::{SpeciesDef, resolve, step_order};
+use ecosim::util::*;
+use ecosim::world::World;
+
+const FOOD_WEB: &str = r##"
+world_w = 60
+world_h = 40
+
+[species.herb]
+init = 200
+
+[species.grazer]  # plants only, like herb
+init = 150
+color = "#59d97a"
+shape = "square"
+
+[species.pred]
+eats = ["herb", "grazer"]
+init = 12
+
+[species.apex]
+eats = ["pred"]
+init = 3
+"##;
+
+#[test]
+fn species_sections_replace_the_defaults() {
+    let set = SimSettings::from_text(FOOD_WEB).unwrap();
+    let names: Vec<&str> = set.species.iter().map(|d| d.name.as_str()).collect();
+    assert_eq!(names, ["herb", "grazer", "pred", "apex"]);
+    assert_eq!(set.species[1].color, [0x59, 0xd9, 0x7a]);
+    assert_eq!(set.species[1].speed, SpeciesDef::herb().speed);
+
+    let sp = resolve(&set.species);
+    assert_eq!(sp[2].prey, [0, 1]);
+    assert_eq!(sp[0].threats, [2]);
+    assert!(sp[1].eats_plants && !sp[3].eats_plants);
+    assert_eq!(step_order(&sp), [3, 2, 0, 1]);
+
+    assert_eq!(SimSettings::from_text(&set.to_text()).unwrap(), set);
+}
+
+#[test]
+fn bad_food_webs_are_rejected() {
+    for text in [
+        "[species.a]\neats = [\"b\"]",
+        "[species.a]\neats = [\"a\"]",
+        "[species.a]\n[species.a]",
+        "[species.plants]",
+        "[species.a]\nshape = \"blob\"",
+        "[species.a]\ngraze_e = -0.1",
+        "[species.a]\nkill_e = NaN",
+        "[species.a]\nrepro_e = inf",
+        "[species.a]\nchild_e = -1",
+        "[species.a]\nchild_spread = 1000",
+        "[species.a]\nrespawn_e = -2",
+    ] {
+        assert!(SimSettings::from_text(text).is_err(), "accepted {:?}", text);
+    }
+    let e = SimSettings::from_text("[species.plants]").unwrap_err();
+    assert!(e.contains("reserved"), "{}", e);
+}
+
+#[test]
+fn legacy_keys_still_set_herb_and_pred() {
+    let set = SimSettings::from_text("init_herbs = 300\npred_met = 0.03").unwrap();
+    assert_eq!(set.species[0].init, 300);
+    assert_eq!(set.species[1].met, 0.03);
+}
+
+#[test]
+fn food_web_runs() {
+    let set = SimSettings::from_text(FOOD_WEB).unwrap();
+    let mut rng = rng_from_seed(5);
+    let mut world = World::new(&mut rng, &set);
+    assert_eq!(world.counts().pops.iter().map(|p| p.n).collect::<Vec<_>>(), [200, 150, 12, 3]);
+
+    let mut eaten = [0u32; 4];
+    for _ in 0..300 {
+        let d = world.step(&mut rng, &set, 1.0 / 60.0);
+        for (e, n) in eaten.iter_mut().zip(&d.deaths) {
+            *e += n;
+        }
+    }
+    assert_eq!(world.counts().pops.len(), 4);
+    assert!(eaten[0] + eaten[1] > 0);
+}
@@ -0,0 +1,66 @@
+use ecosim::config::*;
+use ecosim::sweep::*;
+
+#[test]
+fn axes_parse_ranges_and_lists() {
+    let a = Axis::parse("pred.met=0.01:0.03:5").unwrap();
+    assert_eq!(a.key, "pred.met");
+    assert_eq!(a.values, ["0.01", "0.015", "0.02", "0.025", "0.03"]);
+    assert_eq!(Axis::parse("world_w=100:200:3").unwrap().values, ["100", "150", "200"]);
+    assert_eq!(Axis::parse("herb_met = 0.01, 0.02").unwrap().values, ["0.01", "0.02"]);
+    for bad in ["pred.met", "x=1:2", "x=1:2:0", "x=a:2:3", "x="] {
+        assert!(Axis::parse(bad).is_err(), "accepted {:?}", bad);
+    }
+}
+
+#[test]
+fn points_cover_every_combination() {
+    let axes = [Axis::parse("herb_met=0.01,0.02").unwrap(), Axis::parse("pred.speed=0.3:0.4:3").unwrap()];
+    let pts = points(&SimSettings::default(), &axes).unwrap();
+    assert_eq!(pts.len(), 6);
+    assert_eq!(pts[4].values, ["0.02", "0.35"]);
+    assert_eq!(pts[4].set.species[0].met, 0.02);
+    assert_eq!(pts[4].set.species[1].speed, 0.35);
+
+    assert!(points(&SimSettings::default(), &[Axis::parse("nope=1,2").unwrap()]).is_err());
+    assert!(points(&SimSettings::default(), &[Axis::parse("plant_spread=0.5,2").unwrap()]).is_err());
+}
+
+#[test]
+fn period_finds_cycles_and_ignores_noise() {
+    let wave: Vec<f64> = (0..2000).map(|i| 100.0 + 40.0 * (i as f64 * std::f64::consts::TAU / 250.0).sin()).collect();
+    let p = period(&wave).unwrap();
+    assert!((p - 250.0).abs() < 1.0, "period {}", p);
+
+    let flat = vec![7.0; 500];
+    assert_eq!(period(&flat), None);
+    // one bump isn't a cycle
+    let bump: Vec<f64> = (0..500).map(|i| if (200..300).contains(&i) { 50.0 } else { 10.0 }).collect();
+    assert_eq!(period(&bump), None);
+}
+
+#[test]
+fn aggregate_pools_runs() {
+    let pop = |extinct_at, mean, var, period| PopSummary { extinct_at, mean, var, period };
+    let runs = [
+        RunSummary { seed: 1, pops: vec![pop(Some(100), 10.0, 4.0, Some(300.0))] },
+        RunSummary { seed: 2, pops: vec![pop(None, 20.0, 6.0, None)] },
+    ];
+    let a = &aggregate(&runs)[0];
+    assert_eq!(a.extinct_frac, 0.5);
+    assert_eq!(a.extinct_step, Some(100.0));
+    assert_eq!(a.mean, 15.0);
+    // mean of variances plus variance of means
+    assert_eq!(a.var, 5.0 + 25.0);
+    assert_eq!(a.period, Some(300.0));
+}
+
+#[test]
+fn runs_repeat_exactly() {
+    let mut set = SimSettings { world_w: 40, world_h: 30, ..SimSettings::default() };
+    set.species[0].init = 150;
+    set.species[1].init = 8;
+    let a = run_one(&set, 3, 200, 50);
+    assert_eq!(a, run_one(&set, 3, 200, 50));
+    assert_eq!(a.pops.len(), 2);
+}
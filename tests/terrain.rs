@@ -0,0 +1,91 @@
+use ecosim::config::*;
+use ecosim::save::RunState;
+use ecosim::terrain::{Ground, GroundMap, TerrainSource, generate};
+use ecosim::util::*;
+use ecosim::world::{Space, World};
+
+#[test]
+fn images_read_as_terrain() {
+    // one pixel of each kind, ascii with a comment, then the same as binary
+    let ascii = b"P3\n# rock water fertile barren plain\n5 1\n255\n10 10 10  20 40 200  30 180 40  200 160 60  230 230 230\n";
+    let want = vec![Ground::Rock, Ground::Water, Ground::Fertile, Ground::Barren, Ground::Plain];
+    assert_eq!(GroundMap::from_pnm(ascii).unwrap().cells, want);
+
+    let mut bin = b"P6 5 1 255\n".to_vec();
+    bin.extend_from_slice(&[10, 10, 10, 20, 40, 200, 30, 180, 40, 200, 160, 60, 230, 230, 230]);
+    assert_eq!(GroundMap::from_pnm(&bin).unwrap().cells, want);
+
+    // 2x2 stretched over a 4x2 world, nearest pixel
+    let map = GroundMap::from_pnm(b"P2 2 2 15\n0 15\n15 0\n").unwrap();
+    let fit = map.fit(Space::new(4, 2));
+    use Ground::*;
+    assert_eq!(fit, vec![Rock, Rock, Plain, Plain, Plain, Plain, Rock, Rock]);
+
+    for bad in [&b"P6 2 2 255\n\x00"[..], b"P3 0 1 255\n", b"GIF89a", b"P2 2 2\n"] {
+        assert!(GroundMap::from_pnm(bad).is_err(), "accepted {:?}", bad);
+    }
+
+    // settings read the image when they're parsed
+    let path = std::env::temp_dir().join(format!("ecosim_terrain_{}.ppm", std::process::id()));
+    std::fs::write(&path, ascii).unwrap();
+    let text = format!("terrain = \"{}\"", path.display());
+    let set = SimSettings::from_text(&text).unwrap();
+    assert_eq!(SimSettings::from_text(&set.to_text()).unwrap(), set);
+    std::fs::remove_file(&path).ok();
+    assert!(SimSettings::from_text(&text).is_err());
+    assert_eq!(SimSettings::from_text("terrain = \"random\"").unwrap().terrain, TerrainSource::Random);
+}
+
+#[test]
+fn huge_headers_with_short_bodies_fail_cleanly() {
+    // a few bytes claiming 16384x16384, read as an error and not an
+    // allocation that takes the process down
+    for bad in [&b"P6 16384 16384 255\n\x00\x00\x00"[..], b"P5 16384 16384 65535\n\x00", b"P3 16384 16384 255\n0 0 0\n"] {
+        assert_eq!(GroundMap::from_pnm(bad).err().as_deref(), Some("image data is cut short"));
+    }
+}
+
+#[test]
+fn rock_and_water_shape_the_run() {
+    let mut set = SimSettings { world_w: 96, world_h: 64, terrain: TerrainSource::Random, ..SimSettings::default() };
+    set.species[0].init = 300;
+    set.species[1].init = 20;
+    let dt = SimTuning::default().fixed_dt;
+
+    // same seed, same ground, and it has a bit of everything
+    assert_eq!(generate(Space::new(96, 64), 5), generate(Space::new(96, 64), 5));
+    let mut rng = rng_from_seed(31);
+    let mut world = World::new(&mut rng, &set);
+    for g in [Ground::Plain, Ground::Fertile, Ground::Barren, Ground::Water, Ground::Rock] {
+        assert!(world.ground().contains(&g), "no {}", g.name());
+    }
+
+    let sp = world.space();
+    for _ in 0..300 {
+        world.step(&mut rng, &set, dt);
+        for s in 0..world.species().len() {
+            for a in world.agents(s) {
+                assert_ne!(world.ground()[sp.cell_of(a.p)], Ground::Rock, "agent {} in rock", a.id);
+            }
+        }
+        for (&p, g) in world.plants().iter().zip(world.ground()) {
+            assert!(g.grows() || p == 0);
+        }
+    }
+
+    // saves keep the ground, and so does carrying on from one
+    let path = std::env::temp_dir().join(format!("ecosim_terrain_{}.bin", std::process::id()));
+    let path = path.to_str().unwrap();
+    let run = RunState { seed: 31, steps: 300, speed: 1.0, rng: rng.clone() };
+    world.save(path, &set, &run).unwrap();
+    let mut back = World::load(path).unwrap();
+    std::fs::remove_file(path).ok();
+    assert_eq!(back.world.ground(), world.ground());
+    assert_eq!(back.set.terrain, TerrainSource::Random);
+
+    let mut rng2 = back.run.unwrap().rng;
+    for _ in 0..50 {
+        assert_eq!(world.step(&mut rng, &set, dt), back.world.step(&mut rng2, &back.set, dt));
+    }
+    assert_eq!(world.counts(), back.world.counts());
+}
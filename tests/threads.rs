@@ -0,0 +1,28 @@
+use ecosim::config::*;
+use ecosim::util::*;
+use ecosim::world::World;
+
+type AgentRow = (u32, f32, f32, f32);
+
+// big enough that every phase actually gets split across threads
+fn run(threads: u32, steps: u32) -> (Vec<u8>, Vec<AgentRow>, Vec<AgentRow>) {
+    let mut set = SimSettings { threads, ..SimSettings::default() };
+    set.species[0].init = 3000;
+    set.species[1].init = 1200;
+    let mut rng = rng_from_seed(777);
+    let mut world = World::new(&mut rng, &set);
+    for _ in 0..steps {
+        world.step(&mut rng, &set, 1.0 / 60.0);
+    }
+
+    let agents = |v: &[ecosim::world::Agent]| v.iter().map(|a| (a.id, a.p.x, a.p.y, a.e)).collect();
+    (world.plants().to_vec(), agents(world.agents(0)), agents(world.agents(1)))
+}
+
+#[test]
+fn thread_count_does_not_change_results() {
+    let one = run(1, 40);
+    for t in [2, 3, 8] {
+        assert!(run(t, 40) == one, "{} threads diverged from 1", t);
+    }
+}
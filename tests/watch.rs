@@ -0,0 +1,55 @@
+use ecosim::config::*;
+use ecosim::util::*;
+use ecosim::world::{LifeEvent, TrackTarget, World};
+
+mod common;
+use common::small_settings;
+
+#[test]
+fn watching_an_agent_does_not_change_the_run() {
+    let set = small_settings();
+    let dt = SimTuning::default().fixed_dt;
+
+    let mut rng_a = rng_from_seed(8);
+    let mut a = World::new(&mut rng_a, &set);
+    let mut rng_b = rng_from_seed(8);
+    let mut b = World::new(&mut rng_b, &set);
+    let id = b.agents(1)[0].id;
+    // out of range is dropped, not a panic on the next step
+    b.watch(Some(TrackTarget { species: 7, id }));
+    assert_eq!(b.watching(), None);
+    b.watch(Some(TrackTarget { species: 1, id }));
+
+    for _ in 0..300 {
+        let da = a.step(&mut rng_a, &set, dt);
+        let db = b.step(&mut rng_b, &set, dt);
+        b.take_life_events();
+        assert!((a.counts(), da) == (b.counts(), db));
+    }
+}
+
+#[test]
+fn a_watched_life_ends_in_one_death() {
+    let set = small_settings();
+    let dt = SimTuning::default().fixed_dt;
+
+    let mut rng = rng_from_seed(8);
+    let mut world = World::new(&mut rng, &set);
+    let t = TrackTarget { species: 0, id: world.agents(0)[0].id };
+    world.watch(Some(t));
+
+    let mut events = Vec::new();
+    for _ in 0..3000 {
+        world.step(&mut rng, &set, dt);
+        events.extend(world.take_life_events());
+        if world.tracked_info(t).is_none() {
+            break;
+        }
+    }
+
+    assert!(world.tracked_info(t).is_none(), "still alive after 3000 steps");
+    let deaths = events.iter().filter(|e| matches!(e, LifeEvent::Starved | LifeEvent::Eaten(_))).count();
+    assert_eq!(deaths, 1);
+    assert!(matches!(events.last(), Some(LifeEvent::Starved | LifeEvent::Eaten(_))));
+    assert!(events.iter().any(|e| matches!(e, LifeEvent::Grazed(_))));
+}